@@ -1,3 +1,17 @@
 pub const MAX_FEE_BASIS_POINTS: u16 = 10_000;
+pub const MAX_PAYMENT_MINTS: usize = 8;
+pub const MAX_ARBITERS: usize = 5;
+pub const MAX_CREATORS: usize = 5;
+pub const MAX_AGREEMENTS: usize = 10;
+pub const MAX_AGREEMENT_TITLE_LENGTH: usize = 32;
+pub const MAX_AGREEMENT_DETAILS_LENGTH: usize = 200;
+pub const MAX_NAME_LENGTH: usize = 32;
+pub const MAX_SYMBOL_LENGTH: usize = 10;
+pub const MAX_URI_LENGTH: usize = 200;
+pub const URI_MINT_PLACEHOLDER: &str = "{mint}"; // replaced by the NFT mint in the default URI template
+pub const MAX_PUBKEY_STRING_LENGTH: usize = 44; // base58 encoded public key
+pub const DEFAULT_ORDER_REFUND_TIMEOUT: i64 = 7 * 24 * 60 * 60; // 7 days
+pub const AUCTION_EXTENSION_WINDOW: i64 = 10 * 60; // bids in the last 10 minutes extend the auction
+pub const EXPIRED_ASK_CRANK_REWARD: u64 = 100_000; // lamports taken from the ask rent
 pub const METADATA_URI: &str =
     "https://cloudflare-ipfs.com/ipfs/QmbzJafuKY3B4t25eq9zdKZMgXiMeW4jHLzf6KE6ZmHWn1/02.json";
\ No newline at end of file
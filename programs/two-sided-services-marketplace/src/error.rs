@@ -22,4 +22,132 @@ pub enum ProgramErrorCode {
     InvalidOriginalVendor,
     #[msg("NFT mint of the service account does not match the NFT mint of the ask account.")]
     InvalidNftMint,
+    #[msg("Royalty and protocol fee basis points together exceed 10,000.")]
+    InvalidFeeBasisPoints,
+    #[msg("The proposed admin must be a new, non-default key.")]
+    InvalidPendingAdmin,
+    #[msg("There is no pending admin proposal.")]
+    NoPendingAdmin,
+    #[msg("The treasury does not hold enough funds for this withdrawal.")]
+    InsufficientTreasuryBalance,
+    #[msg("The marketplace is paused.")]
+    MarketplacePaused,
+    #[msg("The service is not listed.")]
+    ServiceNotListed,
+    #[msg("Asker does not match the asker of the ask account.")]
+    InvalidAsker,
+    #[msg("The payment mint is not accepted by the marketplace.")]
+    PaymentMintNotAllowed,
+    #[msg("The marketplace already accepts the maximum number of payment mints.")]
+    TooManyPaymentMints,
+    #[msg("Payment mint does not match the payment mint of the service.")]
+    InvalidPaymentMint,
+    #[msg("A token account or token program required for an SPL token payment is missing.")]
+    MissingTokenAccount,
+    #[msg("Payment mints with a transfer fee or transfer hook are not supported.")]
+    UnsupportedPaymentMint,
+    #[msg("Royalty basis points exceed the maximum set by the marketplace.")]
+    RoyaltyExceedsMaximum,
+    #[msg("A service can have at most 5 creators.")]
+    TooManyCreators,
+    #[msg("Creator shares must add up to 100.")]
+    InvalidCreatorShares,
+    #[msg("Royalty recipient accounts do not match the creators of the service.")]
+    InvalidRoyaltyRecipient,
+    #[msg("The service NFT is not held in marketplace custody.")]
+    ServiceNotInCustody,
+    #[msg("The service NFT is already held in marketplace custody.")]
+    ServiceAlreadyInCustody,
+    #[msg("The service has an open order.")]
+    ServiceHasOpenOrder,
+    #[msg("The timeout must be greater than zero.")]
+    InvalidTimeout,
+    #[msg("The order cannot be refunded before its refund timeout.")]
+    OrderNotRefundable,
+    #[msg("Buyer does not match the buyer of the order.")]
+    InvalidBuyer,
+    #[msg("Vendors cannot buy their own service.")]
+    CannotBuyOwnService,
+    #[msg("A service can have at most 10 agreements.")]
+    TooManyAgreements,
+    #[msg("Agreement amounts must add up to the service price.")]
+    InvalidMilestoneAmounts,
+    #[msg("The order has no milestone left to release.")]
+    NoMilestoneToRelease,
+    #[msg("The vendor has not requested the release of the next milestone.")]
+    MilestoneReleaseNotRequested,
+    #[msg("The marketplace already has the maximum number of arbiters.")]
+    TooManyArbiters,
+    #[msg("The order is under dispute.")]
+    OrderDisputed,
+    #[msg("The order is not under dispute.")]
+    OrderNotDisputed,
+    #[msg("Share basis points exceed 10,000.")]
+    InvalidShareBasisPoints,
+    #[msg("Review score must be between 1 and 5.")]
+    InvalidReviewScore,
+    #[msg("This purchase has already been reviewed.")]
+    PurchaseAlreadyReviewed,
+    #[msg("The purchase receipt account is required.")]
+    MissingReceiptAccount,
+    #[msg("Display name is too long.")]
+    DisplayNameTooLong,
+    #[msg("Contact URI is too long.")]
+    ContactUriTooLong,
+    #[msg("Name is too long.")]
+    NameTooLong,
+    #[msg("Symbol is too long.")]
+    SymbolTooLong,
+    #[msg("URI is too long.")]
+    UriTooLong,
+    #[msg("Agreement not found.")]
+    AgreementNotFound,
+    #[msg("Agreement titles must be unique.")]
+    DuplicateAgreementTitle,
+    #[msg("Agreement title is empty or too long.")]
+    InvalidAgreementTitle,
+    #[msg("Agreement details are too long.")]
+    AgreementDetailsTooLong,
+    #[msg("The last agreement of a service paid in milestones cannot be removed.")]
+    LastMilestoneAgreement,
+    #[msg("Every open ask has to be refunded before the service is closed.")]
+    ServiceHasOpenAsks,
+    #[msg("Expiry must be in the future.")]
+    InvalidExpiry,
+    #[msg("The service listing has expired.")]
+    ListingExpired,
+    #[msg("The ask has expired.")]
+    AskExpired,
+    #[msg("The ask has not expired yet.")]
+    AskNotExpired,
+    #[msg("The service is being auctioned.")]
+    ServiceInAuction,
+    #[msg("The auction has ended.")]
+    AuctionEnded,
+    #[msg("The auction has not ended yet.")]
+    AuctionNotEnded,
+    #[msg("The bid is below the reserve price or the minimum increment.")]
+    BidTooLow,
+    #[msg("The auction already has a bid.")]
+    AuctionHasBids,
+    #[msg("Invalid bidder.")]
+    InvalidBidder,
+    #[msg("Invalid Dutch auction schedule.")]
+    InvalidDutchAuction,
+    #[msg("The price is above the maximum price accepted by the buyer.")]
+    PriceAboveMaximum,
+    #[msg("Services in a Dutch auction are bought with buy_service.")]
+    ServiceInDutchAuction,
+    #[msg("The ask price is below the minimum price accepted by the vendor.")]
+    AskPriceBelowMinimum,
+    #[msg("Inventory is already enabled for this service.")]
+    InventoryAlreadyEnabled,
+    #[msg("Inventory is not enabled for this service.")]
+    InventoryNotEnabled,
+    #[msg("The number of units must be greater than zero.")]
+    InvalidUnits,
+    #[msg("The service is sold out.")]
+    ServiceSoldOut,
+    #[msg("Invalid unit mint.")]
+    InvalidUnitMint,
 }
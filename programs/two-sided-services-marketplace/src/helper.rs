@@ -1,11 +1,32 @@
-use anchor_lang::prelude::*;
-use anchor_spl::{token_2022, token_interface::Mint};
+use anchor_lang::{prelude::*, solana_program::program::invoke_signed};
+use anchor_spl::{
+    token_2022::{
+        self,
+        spl_token_2022::{
+            self,
+            extension::{BaseStateWithExtensions, StateWithExtensions},
+        },
+    },
+    token_interface::{
+        self, spl_token_metadata_interface::state::TokenMetadata, Mint, TokenAccount,
+        TokenInterface,
+    },
+};
+
+use crate::{
+    constant::{
+        MAX_AGREEMENT_DETAILS_LENGTH, MAX_AGREEMENT_TITLE_LENGTH, MAX_CREATORS,
+        MAX_FEE_BASIS_POINTS,
+    },
+    error::ProgramErrorCode,
+    states::Creator,
+};
 
 pub fn send_lamports<'a>(from: AccountInfo<'a>, to: AccountInfo<'a>, amount: u64) -> Result<()> {
     let ix = anchor_lang::solana_program::system_instruction::transfer(
         &from.key(),
         &to.key(),
-        amount.into(),
+        amount,
     );
 
     anchor_lang::solana_program::program::invoke(
@@ -15,11 +36,75 @@ pub fn send_lamports<'a>(from: AccountInfo<'a>, to: AccountInfo<'a>, amount: u64
     .map_err(|err| err.into())
 }
 
+// Moves lamports out of an account owned by this program without a CPI
+pub fn withdraw_lamports<'a>(from: &AccountInfo<'a>, to: &AccountInfo<'a>, amount: u64) -> Result<()> {
+    let from_lamports = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(ProgramErrorCode::Overflow)?;
+    let to_lamports = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(ProgramErrorCode::Overflow)?;
+
+    **from.try_borrow_mut_lamports()? = from_lamports;
+    **to.try_borrow_mut_lamports()? = to_lamports;
+
+    Ok(())
+}
+
+pub fn calculate_fee(amount: u64, fee_basis_points: u16) -> Result<u64> {
+    let fee = (amount as u128)
+        .checked_mul(fee_basis_points as u128)
+        .ok_or(ProgramErrorCode::Overflow)?
+        .checked_div(MAX_FEE_BASIS_POINTS as u128)
+        .ok_or(ProgramErrorCode::DivideByZero)?;
+
+    Ok(fee as u64)
+}
+
+// Royalty and protocol fee are both taken out of the same price, so together they cannot exceed 100%
+pub fn validate_fee_basis_points(
+    royalty_fee_basis_points: u16,
+    protocol_fee_basis_points: u16,
+) -> Result<()> {
+    let total_fee_basis_points =
+        royalty_fee_basis_points as u32 + protocol_fee_basis_points as u32;
+
+    if total_fee_basis_points > MAX_FEE_BASIS_POINTS as u32 {
+        return Err(ProgramErrorCode::InvalidFeeBasisPoints.into());
+    }
+
+    Ok(())
+}
+
+pub fn transfer_nft_from_user_to_pool<'info>(
+    from: AccountInfo<'info>,
+    to_pool: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program_2022: AccountInfo<'info>,
+) -> Result<()> {
+    token_2022::transfer_checked(
+        CpiContext::new(
+            token_program_2022,
+            token_2022::TransferChecked {
+                from,
+                to: to_pool,
+                authority,
+                mint: mint.to_account_info(),
+            },
+        ),
+        1,
+        mint.decimals,
+    )
+}
+
 pub fn transfer_nft_from_pool_to_user<'info>(
     from_pool: AccountInfo<'info>,
     to: AccountInfo<'info>,
     authority: AccountInfo<'info>,
-    mint: Box<InterfaceAccount<'info, Mint>>,
+    mint: &InterfaceAccount<'info, Mint>,
     token_program_2022: AccountInfo<'info>,
     signer_seeds: &[&[&[u8]]],
 ) -> Result<()> {
@@ -41,3 +126,336 @@ pub fn transfer_nft_from_pool_to_user<'info>(
         mint.decimals,
     )
 }
+
+pub fn validate_creators(creators: &[Creator]) -> Result<()> {
+    if creators.len() > MAX_CREATORS {
+        return Err(ProgramErrorCode::TooManyCreators.into());
+    }
+
+    let total_share: u32 = creators.iter().map(|creator| creator.share as u32).sum();
+
+    if total_share != 100 {
+        return Err(ProgramErrorCode::InvalidCreatorShares.into());
+    }
+
+    Ok(())
+}
+
+// Splits a royalty by creator share, the rounding dust goes to the first creator
+pub fn split_royalty(royalty_amount: u64, creators: &[Creator]) -> Result<Vec<u64>> {
+    let mut amounts = creators
+        .iter()
+        .map(|creator| {
+            (royalty_amount as u128)
+                .checked_mul(creator.share as u128)
+                .map(|amount| (amount / 100) as u64)
+                .ok_or(ProgramErrorCode::Overflow.into())
+        })
+        .collect::<Result<Vec<u64>>>()?;
+
+    let distributed: u64 = amounts.iter().sum();
+    let dust = royalty_amount
+        .checked_sub(distributed)
+        .ok_or(ProgramErrorCode::Overflow)?;
+
+    if let Some(first) = amounts.first_mut() {
+        *first = first.checked_add(dust).ok_or(ProgramErrorCode::Overflow)?;
+    }
+
+    Ok(amounts)
+}
+
+// Mint and token program used when a service is paid in an SPL token instead of SOL
+#[derive(Clone, Copy)]
+pub struct TokenPayment<'a, 'info> {
+    pub mint: &'a InterfaceAccount<'info, Mint>,
+    pub token_program: &'a Interface<'info, TokenInterface>,
+}
+
+pub fn optional_account_info<'info, T: ToAccountInfo<'info>>(
+    account: &Option<Box<T>>,
+) -> Option<AccountInfo<'info>> {
+    account.as_ref().map(|account| account.to_account_info())
+}
+
+pub fn token_payment<'a, 'info>(
+    payment_mint: &'a Option<Box<InterfaceAccount<'info, Mint>>>,
+    token_program: &'a Option<Interface<'info, TokenInterface>>,
+) -> Result<Option<TokenPayment<'a, 'info>>> {
+    match (payment_mint, token_program) {
+        (Some(mint), Some(token_program)) => Ok(Some(TokenPayment {
+            mint,
+            token_program,
+        })),
+        (None, _) => Ok(None),
+        (Some(_), None) => Err(ProgramErrorCode::MissingTokenAccount.into()),
+    }
+}
+
+// Pays `amount` in SOL, or in the payment token when `token_payment` is set.
+// `pda_signer_seeds` is set when `from` is an account of this program rather than a wallet.
+pub fn transfer_payment<'info>(
+    from: AccountInfo<'info>,
+    from_token_account: Option<AccountInfo<'info>>,
+    to: AccountInfo<'info>,
+    to_token_account: Option<AccountInfo<'info>>,
+    token_payment: Option<&TokenPayment<'_, 'info>>,
+    amount: u64,
+    pda_signer_seeds: Option<&[&[&[u8]]]>,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let Some(token_payment) = token_payment else {
+        return match pda_signer_seeds {
+            Some(_) => withdraw_lamports(&from, &to, amount),
+            None => send_lamports(from, to, amount),
+        };
+    };
+
+    let (Some(from_token_account), Some(to_token_account)) = (from_token_account, to_token_account)
+    else {
+        return Err(ProgramErrorCode::MissingTokenAccount.into());
+    };
+
+    let cpi_accounts = token_interface::TransferChecked {
+        from: from_token_account,
+        to: to_token_account,
+        authority: from,
+        mint: token_payment.mint.to_account_info(),
+    };
+    let cpi_program = token_payment.token_program.to_account_info();
+
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(cpi_program, cpi_accounts, pda_signer_seeds.unwrap_or(&[])),
+        amount,
+        token_payment.mint.decimals,
+    )
+}
+
+pub fn close_token_account<'info>(
+    token_account: AccountInfo<'info>,
+    destination: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    token_interface::close_account(CpiContext::new_with_signer(
+        token_program,
+        token_interface::CloseAccount {
+            account: token_account,
+            destination,
+            authority,
+        },
+        signer_seeds,
+    ))
+}
+
+// Pays the royalty to every creator of a service. `recipients` are passed as remaining accounts
+// in creator order: the creator wallets for SOL payments, their token accounts for SPL payments.
+pub fn pay_royalties<'info>(
+    from: AccountInfo<'info>,
+    from_token_account: Option<AccountInfo<'info>>,
+    creators: &[Creator],
+    recipients: &[AccountInfo<'info>],
+    token_payment: Option<&TokenPayment<'_, 'info>>,
+    royalty_amount: u64,
+    pda_signer_seeds: Option<&[&[&[u8]]]>,
+) -> Result<()> {
+    if royalty_amount == 0 {
+        return Ok(());
+    }
+
+    if recipients.len() < creators.len() {
+        return Err(ProgramErrorCode::InvalidRoyaltyRecipient.into());
+    }
+
+    let amounts = split_royalty(royalty_amount, creators)?;
+
+    for ((creator, recipient), amount) in creators.iter().zip(recipients).zip(amounts) {
+        let to_token_account = match token_payment {
+            Some(token_payment) => {
+                if recipient.owner != &token_payment.token_program.key() {
+                    return Err(ProgramErrorCode::InvalidRoyaltyRecipient.into());
+                }
+
+                let token_account =
+                    TokenAccount::try_deserialize(&mut &recipient.try_borrow_data()?[..])?;
+
+                if token_account.owner != creator.address
+                    || token_account.mint != token_payment.mint.key()
+                {
+                    return Err(ProgramErrorCode::InvalidRoyaltyRecipient.into());
+                }
+
+                Some(recipient.clone())
+            }
+            None => {
+                if recipient.key() != creator.address {
+                    return Err(ProgramErrorCode::InvalidRoyaltyRecipient.into());
+                }
+
+                None
+            }
+        };
+
+        transfer_payment(
+            from.clone(),
+            from_token_account.clone(),
+            recipient.clone(),
+            to_token_account,
+            token_payment,
+            amount,
+            pda_signer_seeds,
+        )?;
+    }
+
+    Ok(())
+}
+
+// Where the money of a sale comes from and goes to, for SOL or an SPL token
+pub struct SalePayment<'a, 'info> {
+    pub from: AccountInfo<'info>,
+    pub from_token_account: Option<AccountInfo<'info>>,
+    pub vendor: AccountInfo<'info>,
+    pub vendor_token_account: Option<AccountInfo<'info>>,
+    pub treasury: AccountInfo<'info>,
+    pub treasury_token_account: Option<AccountInfo<'info>>,
+    pub token_payment: Option<TokenPayment<'a, 'info>>,
+    // Set when `from` is an escrow account of this program rather than the buyer's wallet
+    pub pda_signer_seeds: Option<&'a [&'a [&'a [u8]]]>,
+}
+
+#[derive(Default)]
+pub struct SaleAmounts {
+    pub royalty_amount: u64,
+    pub protocol_fee_amount: u64,
+    pub remaining_amount: u64,
+}
+
+// Pays the protocol fee to the treasury, the royalty to the creators and the rest to the vendor.
+// `royalty_basis_points` is zero when the vendor is the original vendor.
+pub fn settle_sale<'info>(
+    payment: &SalePayment<'_, 'info>,
+    price: u64,
+    protocol_fee_basis_points: u16,
+    royalty_basis_points: u16,
+    creators: &[Creator],
+    royalty_recipients: &[AccountInfo<'info>],
+) -> Result<SaleAmounts> {
+    let protocol_fee_amount = calculate_fee(price, protocol_fee_basis_points)?;
+    let royalty_amount = calculate_fee(price, royalty_basis_points)?;
+    let remaining_amount = price
+        .checked_sub(royalty_amount)
+        .and_then(|amount| amount.checked_sub(protocol_fee_amount))
+        .ok_or(ProgramErrorCode::Overflow)?;
+
+    pay_royalties(
+        payment.from.clone(),
+        payment.from_token_account.clone(),
+        creators,
+        royalty_recipients,
+        payment.token_payment.as_ref(),
+        royalty_amount,
+        payment.pda_signer_seeds,
+    )?;
+
+    transfer_payment(
+        payment.from.clone(),
+        payment.from_token_account.clone(),
+        payment.treasury.clone(),
+        payment.treasury_token_account.clone(),
+        payment.token_payment.as_ref(),
+        protocol_fee_amount,
+        payment.pda_signer_seeds,
+    )?;
+
+    transfer_payment(
+        payment.from.clone(),
+        payment.from_token_account.clone(),
+        payment.vendor.clone(),
+        payment.vendor_token_account.clone(),
+        payment.token_payment.as_ref(),
+        remaining_amount,
+        payment.pda_signer_seeds,
+    )?;
+
+    Ok(SaleAmounts {
+        royalty_amount,
+        protocol_fee_amount,
+        remaining_amount,
+    })
+}
+
+// Listings and asks without an expiry never expire
+pub fn has_expired(expires_at: Option<i64>) -> Result<bool> {
+    match expires_at {
+        Some(expires_at) => Ok(Clock::get()?.unix_timestamp >= expires_at),
+        None => Ok(false),
+    }
+}
+
+pub fn validate_expiry(expires_at: Option<i64>) -> Result<()> {
+    if has_expired(expires_at)? {
+        return Err(ProgramErrorCode::InvalidExpiry.into());
+    }
+    Ok(())
+}
+
+// Agreements are stored as metadata key/value pairs, this bounds the size of the mint
+pub fn validate_agreement(title: &str, details: &str) -> Result<()> {
+    if title.is_empty() || title.len() > MAX_AGREEMENT_TITLE_LENGTH {
+        return Err(ProgramErrorCode::InvalidAgreementTitle.into());
+    }
+    if details.len() > MAX_AGREEMENT_DETAILS_LENGTH {
+        return Err(ProgramErrorCode::AgreementDetailsTooLong.into());
+    }
+    Ok(())
+}
+
+// Reads the Token-2022 metadata stored on a service NFT mint
+pub fn read_token_metadata(mint: &AccountInfo) -> Result<TokenMetadata> {
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    Ok(state.get_variable_len_extension::<TokenMetadata>()?)
+}
+
+// Token-2022 reallocates the mint when its metadata grows, the payer covers the extra rent
+pub fn top_up_rent<'a>(payer: AccountInfo<'a>, account: AccountInfo<'a>, new_len: usize) -> Result<()> {
+    let required_lamports = Rent::get()?.minimum_balance(new_len);
+    let current_lamports = account.lamports();
+
+    if required_lamports > current_lamports {
+        send_lamports(payer, account, required_lamports - current_lamports)?;
+    }
+
+    Ok(())
+}
+
+// Returns the lamports above the rent of a service NFT mint, e.g. after its metadata shrank.
+// Token-2022 only allows this while the config PDA is the mint authority
+pub fn withdraw_excess_mint_lamports<'a>(
+    nft_mint: AccountInfo<'a>,
+    destination: AccountInfo<'a>,
+    config_account: AccountInfo<'a>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    if nft_mint.lamports() <= Rent::get()?.minimum_balance(nft_mint.data_len()) {
+        return Ok(());
+    }
+
+    invoke_signed(
+        &spl_token_2022::instruction::withdraw_excess_lamports(
+            &spl_token_2022::id(),
+            nft_mint.key,
+            destination.key,
+            config_account.key,
+            &[],
+        )?,
+        &[nft_mint, destination, config_account],
+        signer_seeds,
+    )?;
+
+    Ok(())
+}
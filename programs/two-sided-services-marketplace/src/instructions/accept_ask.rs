@@ -1,9 +1,22 @@
 use std::ops::DerefMut;
 
 use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
 
 use crate::{
-    constant::MAX_FEE_BASIS_POINTS, error::ProgramErrorCode, states::{ConfigAccount, ServiceAccount}, AskAccount
+    error::ProgramErrorCode,
+    helper::{
+        close_token_account, has_expired, optional_account_info, settle_sale, token_payment,
+        SaleAmounts, SalePayment,
+    },
+    states::{
+        ConfigAccount, PurchaseReceiptAccount, ServiceAccount, TreasuryAccount,
+        VendorProfileAccount, RECEIPT_SEED, TREASURY_SEED, VENDOR_PROFILE_SEED,
+    },
+    AskAccount, ASK_SEED,
 };
 
 #[derive(Accounts)]
@@ -11,64 +24,211 @@ pub struct AccpectAsk<'info> {
     #[account(mut)]
     pub vendor: Signer<'info>,
     /// CHECK: This account is the asker.
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = asker.key() != vendor.key() @ProgramErrorCode::CannotBuyOwnService
+    )]
     pub asker: AccountInfo<'info>,
-    /// CHECK: This account is the original vendor and is used to send lamports.
-    #[account(mut)]
+    /// CHECK: This account is the original vendor, royalties go to the creators of the service.
     pub original_vendor: AccountInfo<'info>,
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = !config_account.paused @ProgramErrorCode::MarketplacePaused
+    )]
     pub config_account: Account<'info, ConfigAccount>,
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [&TREASURY_SEED.as_bytes()],
+        bump = treasury_account.bump
+    )]
+    pub treasury_account: Account<'info, TreasuryAccount>,
+    // The ask is settled once, its rent goes back to the asker
+    #[account(
+        mut,
+        close = asker,
+        seeds = [&ASK_SEED.as_bytes(), ask_account.nft_mint.as_ref(), asker.key().as_ref()],
+        bump = ask_account.bump,
+        constraint = ask_account.asker == asker.key() @ProgramErrorCode::InvalidAsker,
+        constraint = ask_account.payment_mint == payment_mint.as_ref().map(|mint| mint.key()) @ProgramErrorCode::InvalidPaymentMint
+    )]
     pub ask_account: Account<'info, AskAccount>,
     #[account(
         mut, 
         constraint = service_account.current_vendor == vendor.key() @ProgramErrorCode::InvalidCurrentVendor,
         constraint = service_account.original_vendor == original_vendor.key() @ProgramErrorCode::InvalidOriginalVendor,
-        constraint = service_account.nft_mint == ask_account.nft_mint @ProgramErrorCode::InvalidNftMint
+        constraint = service_account.nft_mint == ask_account.nft_mint @ProgramErrorCode::InvalidNftMint,
+        constraint = service_account.is_listed @ProgramErrorCode::ServiceNotListed,
+        constraint = service_account.is_in_custody @ProgramErrorCode::ServiceNotInCustody,
+        constraint = service_account.open_orders == 0 @ProgramErrorCode::ServiceHasOpenOrder,
+        constraint = service_account.unit_mint.is_none() @ProgramErrorCode::InventoryAlreadyEnabled,
+        constraint = !service_account.is_in_auction @ProgramErrorCode::ServiceInAuction
     )]
     pub service_account: Account<'info, ServiceAccount>,
+    // Proves the purchase when the asker submits a review
+    #[account(
+        init_if_needed,
+        payer = vendor,
+        space = PurchaseReceiptAccount::INIT_SPACE,
+        seeds = [&RECEIPT_SEED.as_bytes(), ask_account.nft_mint.as_ref(), asker.key().as_ref()],
+        bump
+    )]
+    pub receipt_account: Box<Account<'info, PurchaseReceiptAccount>>,
+    #[account(
+        init_if_needed,
+        payer = vendor,
+        space = VendorProfileAccount::INIT_SPACE,
+        seeds = [&VENDOR_PROFILE_SEED.as_bytes(), vendor.key().as_ref()],
+        bump
+    )]
+    pub vendor_profile: Box<Account<'info, VendorProfileAccount>>,
+
+    // The accounts below are only needed when the ask is paid in an SPL token
+    pub payment_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = ask_account,
+        associated_token::token_program = token_program
+    )]
+    pub ask_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = vendor,
+        token::token_program = token_program
+    )]
+    pub vendor_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        init_if_needed,
+        payer = vendor,
+        associated_token::mint = payment_mint,
+        associated_token::authority = treasury_account,
+        associated_token::token_program = token_program
+    )]
+    pub treasury_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
 
     pub rent: Sysvar<'info, Rent>,
 
     pub system_program: Program<'info, System>,
 }
 
-pub fn accept_ask(ctx: Context<AccpectAsk>) -> Result<()> {
-  let ask_account = ctx.accounts.ask_account.deref_mut();
-  let config_account = &ctx.accounts.config_account;
-  let service_account = ctx.accounts.service_account.deref_mut();
-
-  let ask_price = ask_account.ask_price;
-  let remaining_amount;
-
-  // If the vendor is different from the original vendor, calculate and transfer the royalty fee
-  if ctx.accounts.vendor.key() != ctx.accounts.original_vendor.key() {
-      let royalty_fee_basis_points = config_account.royalty_fee_basis_points as u128;
-
-      // Calculate the royalty fee
-      let royalty_amount = (ask_price as u128)
-          .checked_mul(royalty_fee_basis_points)
-          .ok_or(ProgramErrorCode::Overflow)?
-          .checked_div(MAX_FEE_BASIS_POINTS.into())  // Assuming basis points are out of 10,000
-          .ok_or(ProgramErrorCode::DivideByZero)? as u64;
-
-      remaining_amount = ask_price
-          .checked_sub(royalty_amount)
-          .ok_or(ProgramErrorCode::Overflow)?;
-
-      // Transfer royalty fee to the original vendor
-      **ctx.accounts.ask_account.to_account_info().try_borrow_mut_lamports()? -= royalty_amount;
-      **ctx.accounts.original_vendor.to_account_info().try_borrow_mut_lamports()? += royalty_amount;
-  } else {
-      remaining_amount = ask_price;
-  }
-
-  // Transfer the remaining amount to the current vendor
-  **ctx.accounts.ask_account.to_account_info().try_borrow_mut_lamports()? -= remaining_amount;
-  **ctx.accounts.vendor.to_account_info().try_borrow_mut_lamports()? += remaining_amount;
-
-  // Update the current vendor to the asker
-  service_account.current_vendor = ctx.accounts.ask_account.asker;
-
-  Ok(())
-}
\ No newline at end of file
+#[event]
+pub struct AcceptAskEvent {
+    pub vendor: Pubkey,
+    pub asker: Pubkey,
+    pub original_vendor: Pubkey,
+    pub nft_mint: Pubkey,
+    pub payment_mint: Option<Pubkey>,
+    pub ask_price: u64,
+    pub royalty_amount: u64,
+    pub protocol_fee_amount: u64,
+    pub remaining_amount: u64,
+}
+
+// Royalty recipients are passed as remaining accounts, see `pay_royalties`
+pub fn accept_ask<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, AccpectAsk<'info>>,
+    min_price: u64,
+) -> Result<()> {
+    let accounts = &ctx.accounts;
+    let config_account = &accounts.config_account;
+    let service_account = &accounts.service_account;
+    let ask_account = &accounts.ask_account;
+    if has_expired(ask_account.expires_at)? {
+        return Err(ProgramErrorCode::AskExpired.into());
+    }
+
+    let token_payment = token_payment(&accounts.payment_mint, &accounts.token_program)?;
+    let token_program = token_payment
+        .as_ref()
+        .map(|token_payment| token_payment.token_program.to_account_info());
+    let ask_token_account = optional_account_info(&accounts.ask_token_account);
+
+    let nft_mint = ask_account.nft_mint;
+    let asker = ask_account.asker;
+    let signer: &[&[&[u8]]] = &[&[
+        ASK_SEED.as_bytes(),
+        nft_mint.as_ref(),
+        asker.as_ref(),
+        &[ask_account.bump],
+    ]];
+
+    // The asker may have lowered the ask with update_ask_price since the vendor looked at it
+    let ask_price = ask_account.ask_price;
+    if ask_price < min_price {
+        return Err(ProgramErrorCode::AskPriceBelowMinimum.into());
+    }
+
+    // Pay the protocol fee, the royalties if this is a resale and the current vendor out of the escrow
+    let SaleAmounts {
+        royalty_amount,
+        protocol_fee_amount,
+        remaining_amount,
+    } = settle_sale(
+        &SalePayment {
+            from: ask_account.to_account_info(),
+            from_token_account: ask_token_account.clone(),
+            vendor: accounts.vendor.to_account_info(),
+            vendor_token_account: optional_account_info(&accounts.vendor_token_account),
+            treasury: accounts.treasury_account.to_account_info(),
+            treasury_token_account: optional_account_info(&accounts.treasury_token_account),
+            token_payment,
+            pda_signer_seeds: Some(signer),
+        },
+        ask_price,
+        config_account.protocol_fee_basis_points,
+        service_account.resale_royalty_basis_points(),
+        &service_account.creators,
+        ctx.remaining_accounts,
+    )?;
+
+    // The emptied token escrow is closed along with the ask account
+    if let (Some(token_program), Some(ask_token_account)) = (token_program, ask_token_account) {
+        close_token_account(
+            ask_token_account,
+            accounts.asker.to_account_info(),
+            ask_account.to_account_info(),
+            token_program,
+            signer,
+        )?;
+    }
+
+    let payment_mint = ask_account.payment_mint;
+
+    ctx.accounts.receipt_account.record(
+        ctx.bumps.receipt_account,
+        asker,
+        ctx.accounts.vendor.key(),
+        nft_mint,
+        payment_mint,
+        ask_price,
+    )?;
+
+    let vendor_profile = ctx.accounts.vendor_profile.deref_mut();
+    vendor_profile.init_if_needed(ctx.bumps.vendor_profile, ctx.accounts.vendor.key());
+    vendor_profile.record_sale(payment_mint, ask_price)?;
+
+    // Update the current vendor to the asker
+    let service_account = ctx.accounts.service_account.deref_mut();
+    service_account.change_vendor(asker);
+    service_account.open_asks = service_account
+        .open_asks
+        .checked_sub(1)
+        .ok_or(ProgramErrorCode::Overflow)?;
+
+    emit!(AcceptAskEvent {
+        vendor: ctx.accounts.vendor.key(),
+        asker,
+        original_vendor: ctx.accounts.original_vendor.key(),
+        nft_mint,
+        payment_mint,
+        ask_price,
+        royalty_amount,
+        protocol_fee_amount,
+        remaining_amount,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use std::ops::DerefMut;
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::ProgramErrorCode,
+    states::{ConfigAccount, CONFIG_SEED},
+};
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(mut)]
+    pub new_admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [&CONFIG_SEED.as_bytes()],
+        bump = config_account.bump
+    )]
+    pub config_account: Account<'info, ConfigAccount>,
+}
+
+#[event]
+pub struct AcceptAdminEvent {
+    pub old_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
+pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let config_account = ctx.accounts.config_account.deref_mut();
+
+    let pending_admin = config_account
+        .pending_admin
+        .ok_or(ProgramErrorCode::NoPendingAdmin)?;
+
+    // Only the proposed key can complete the handover
+    if ctx.accounts.new_admin.key() != pending_admin {
+        return Err(ProgramErrorCode::Unauthorized.into());
+    }
+
+    let old_admin = config_account.admin;
+    config_account.admin = pending_admin;
+    config_account.pending_admin = None;
+
+    emit!(AcceptAdminEvent {
+        old_admin,
+        new_admin: pending_admin,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use std::ops::DerefMut;
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::ProgramErrorCode,
+    states::{ConfigAccount, CONFIG_SEED},
+};
+
+#[derive(Accounts)]
+pub struct CancelAdminProposal<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [&CONFIG_SEED.as_bytes()],
+        bump = config_account.bump
+    )]
+    pub config_account: Account<'info, ConfigAccount>,
+}
+
+#[event]
+pub struct CancelAdminProposalEvent {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+pub fn cancel_admin_proposal(ctx: Context<CancelAdminProposal>) -> Result<()> {
+    let config_account = ctx.accounts.config_account.deref_mut();
+
+    // Ensure the caller is the admin
+    if ctx.accounts.admin.key() != config_account.admin {
+        return Err(ProgramErrorCode::Unauthorized.into());
+    }
+
+    let pending_admin = config_account
+        .pending_admin
+        .take()
+        .ok_or(ProgramErrorCode::NoPendingAdmin)?;
+
+    emit!(CancelAdminProposalEvent {
+        admin: ctx.accounts.admin.key(),
+        pending_admin,
+    });
+
+    Ok(())
+}
@@ -3,14 +3,18 @@ use std::ops::DerefMut;
 use anchor_lang::prelude::*;
 
 use crate::{
+    constant::{DEFAULT_ORDER_REFUND_TIMEOUT, METADATA_URI},
     error::ProgramErrorCode,
-    states::{ConfigAccount, CONFIG_SEED},
+    helper::validate_fee_basis_points,
+    states::{ConfigAccount, TreasuryAccount, CONFIG_SEED, TREASURY_SEED},
 };
 
 #[event]
 pub struct InitializeEvent {
     pub admin: Pubkey,
     pub royalty_fee_basis_points: u16,
+    pub max_royalty_fee_basis_points: u16,
+    pub protocol_fee_basis_points: u16,
 }
 
 #[derive(Accounts)]
@@ -25,25 +29,57 @@ pub struct Initialize<'info> {
         bump
     )]
     pub config_account: Account<'info, ConfigAccount>,
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = TreasuryAccount::INIT_SPACE,
+        seeds = [&TREASURY_SEED.as_bytes()],
+        bump
+    )]
+    pub treasury_account: Account<'info, TreasuryAccount>,
 
     pub system_program: Program<'info, System>,
 }
 
-pub fn initialize(ctx: Context<Initialize>, royalty_fee_basis_points: u16) -> Result<()> {
+pub fn initialize(
+    ctx: Context<Initialize>,
+    royalty_fee_basis_points: u16,
+    max_royalty_fee_basis_points: u16,
+    protocol_fee_basis_points: u16,
+) -> Result<()> {
     let config_account = ctx.accounts.config_account.deref_mut();
 
     if config_account.is_initialized {
         return Err(ProgramErrorCode::AlreadyInitialized.into());
     }
 
+    if royalty_fee_basis_points > max_royalty_fee_basis_points {
+        return Err(ProgramErrorCode::RoyaltyExceedsMaximum.into());
+    }
+
+    // Vendors may set any royalty up to the maximum, so the maximum is what must fit next to the protocol fee
+    validate_fee_basis_points(max_royalty_fee_basis_points, protocol_fee_basis_points)?;
+
     config_account.bump = ctx.bumps.config_account;
     config_account.is_initialized = true;
     config_account.admin = ctx.accounts.admin.key();
     config_account.royalty_fee_basis_points = royalty_fee_basis_points;
+    config_account.pending_admin = None;
+    config_account.protocol_fee_basis_points = protocol_fee_basis_points;
+    config_account.paused = false;
+    config_account.payment_mints = Vec::new();
+    config_account.max_royalty_fee_basis_points = max_royalty_fee_basis_points;
+    config_account.order_refund_timeout = DEFAULT_ORDER_REFUND_TIMEOUT;
+    config_account.arbiters = Vec::new();
+    config_account.default_uri_template = METADATA_URI.to_string();
+
+    ctx.accounts.treasury_account.bump = ctx.bumps.treasury_account;
 
     emit!(InitializeEvent {
         admin: ctx.accounts.admin.key(),
         royalty_fee_basis_points,
+        max_royalty_fee_basis_points,
+        protocol_fee_basis_points,
     });
 
     Ok(())
@@ -2,4 +2,40 @@ pub mod initialize;
 pub use initialize::*;
 
 pub mod update_royalty;
-pub use  update_royalty::*;
\ No newline at end of file
+pub use  update_royalty::*;
+
+pub mod propose_admin;
+pub use propose_admin::*;
+
+pub mod accept_admin;
+pub use accept_admin::*;
+
+pub mod cancel_admin_proposal;
+pub use cancel_admin_proposal::*;
+
+pub mod update_protocol_fee;
+pub use update_protocol_fee::*;
+
+pub mod withdraw_treasury;
+pub use withdraw_treasury::*;
+
+pub mod set_paused;
+pub use set_paused::*;
+
+pub mod set_payment_mint;
+pub use set_payment_mint::*;
+
+pub mod update_max_royalty;
+pub use update_max_royalty::*;
+
+pub mod update_order_refund_timeout;
+pub use update_order_refund_timeout::*;
+
+pub mod set_arbiter;
+pub use set_arbiter::*;
+
+pub mod set_vendor_verified;
+pub use set_vendor_verified::*;
+
+pub mod update_default_uri_template;
+pub use update_default_uri_template::*;
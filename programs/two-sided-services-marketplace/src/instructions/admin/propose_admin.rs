@@ -0,0 +1,49 @@
+use std::ops::DerefMut;
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::ProgramErrorCode,
+    states::{ConfigAccount, CONFIG_SEED},
+};
+
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [&CONFIG_SEED.as_bytes()],
+        bump = config_account.bump
+    )]
+    pub config_account: Account<'info, ConfigAccount>,
+}
+
+#[event]
+pub struct ProposeAdminEvent {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+    let config_account = ctx.accounts.config_account.deref_mut();
+
+    // Ensure the caller is the admin
+    if ctx.accounts.admin.key() != config_account.admin {
+        return Err(ProgramErrorCode::Unauthorized.into());
+    }
+
+    if new_admin == Pubkey::default() || new_admin == config_account.admin {
+        return Err(ProgramErrorCode::InvalidPendingAdmin.into());
+    }
+
+    // The new admin only takes over once they sign accept_admin
+    config_account.pending_admin = Some(new_admin);
+
+    emit!(ProposeAdminEvent {
+        admin: ctx.accounts.admin.key(),
+        pending_admin: new_admin,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use std::ops::DerefMut;
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    constant::MAX_ARBITERS,
+    error::ProgramErrorCode,
+    states::{ConfigAccount, CONFIG_SEED},
+};
+
+#[derive(Accounts)]
+pub struct SetArbiter<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [&CONFIG_SEED.as_bytes()],
+        bump = config_account.bump
+    )]
+    pub config_account: Account<'info, ConfigAccount>,
+}
+
+#[event]
+pub struct SetArbiterEvent {
+    pub admin: Pubkey,
+    pub arbiter: Pubkey,
+    pub allowed: bool,
+}
+
+pub fn set_arbiter(ctx: Context<SetArbiter>, arbiter: Pubkey, allowed: bool) -> Result<()> {
+    let config_account = ctx.accounts.config_account.deref_mut();
+
+    // Ensure the caller is the admin
+    if ctx.accounts.admin.key() != config_account.admin {
+        return Err(ProgramErrorCode::Unauthorized.into());
+    }
+
+    let is_arbiter = config_account.arbiters.contains(&arbiter);
+
+    if allowed && !is_arbiter {
+        if config_account.arbiters.len() >= MAX_ARBITERS {
+            return Err(ProgramErrorCode::TooManyArbiters.into());
+        }
+        config_account.arbiters.push(arbiter);
+    } else if !allowed && is_arbiter {
+        config_account.arbiters.retain(|key| *key != arbiter);
+    }
+
+    emit!(SetArbiterEvent {
+        admin: ctx.accounts.admin.key(),
+        arbiter,
+        allowed,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use std::ops::DerefMut;
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::ProgramErrorCode,
+    states::{ConfigAccount, CONFIG_SEED},
+};
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [&CONFIG_SEED.as_bytes()],
+        bump = config_account.bump
+    )]
+    pub config_account: Account<'info, ConfigAccount>,
+}
+
+#[event]
+pub struct SetPausedEvent {
+    pub admin: Pubkey,
+    pub paused: bool,
+}
+
+pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+    let config_account = ctx.accounts.config_account.deref_mut();
+
+    // Ensure the caller is the admin
+    if ctx.accounts.admin.key() != config_account.admin {
+        return Err(ProgramErrorCode::Unauthorized.into());
+    }
+
+    config_account.paused = paused;
+
+    emit!(SetPausedEvent {
+        admin: ctx.accounts.admin.key(),
+        paused,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use std::ops::DerefMut;
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    spl_token_2022::{
+        self,
+        extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions},
+    },
+    Mint,
+};
+
+use crate::{
+    constant::MAX_PAYMENT_MINTS,
+    error::ProgramErrorCode,
+    states::{ConfigAccount, CONFIG_SEED},
+};
+
+#[derive(Accounts)]
+pub struct SetPaymentMint<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [&CONFIG_SEED.as_bytes()],
+        bump = config_account.bump
+    )]
+    pub config_account: Account<'info, ConfigAccount>,
+    pub payment_mint: Box<InterfaceAccount<'info, Mint>>,
+}
+
+#[event]
+pub struct SetPaymentMintEvent {
+    pub admin: Pubkey,
+    pub payment_mint: Pubkey,
+    pub allowed: bool,
+}
+
+pub fn set_payment_mint(ctx: Context<SetPaymentMint>, allowed: bool) -> Result<()> {
+    let config_account = ctx.accounts.config_account.deref_mut();
+    let payment_mint = ctx.accounts.payment_mint.key();
+
+    // Ensure the caller is the admin
+    if ctx.accounts.admin.key() != config_account.admin {
+        return Err(ProgramErrorCode::Unauthorized.into());
+    }
+
+    let is_allowed = config_account.payment_mints.contains(&payment_mint);
+
+    if allowed && !is_allowed {
+        if config_account.payment_mints.len() >= MAX_PAYMENT_MINTS {
+            return Err(ProgramErrorCode::TooManyPaymentMints.into());
+        }
+        reject_unsupported_extensions(&ctx.accounts.payment_mint.to_account_info())?;
+        config_account.payment_mints.push(payment_mint);
+    } else if !allowed && is_allowed {
+        // Services already listed with this mint keep settling in it
+        config_account
+            .payment_mints
+            .retain(|mint| *mint != payment_mint);
+    }
+
+    emit!(SetPaymentMintEvent {
+        admin: ctx.accounts.admin.key(),
+        payment_mint,
+        allowed,
+    });
+
+    Ok(())
+}
+
+// Payments are settled by the amount sent, a mint that takes a fee or runs a hook on transfer
+// would leave the escrow and the payees with less than what was recorded
+fn reject_unsupported_extensions(payment_mint: &AccountInfo) -> Result<()> {
+    if *payment_mint.owner != spl_token_2022::id() {
+        return Ok(());
+    }
+
+    let data = payment_mint.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    let has_unsupported_extension = state.get_extension_types()?.iter().any(|extension| {
+        matches!(
+            extension,
+            ExtensionType::TransferFeeConfig | ExtensionType::TransferHook
+        )
+    });
+
+    if has_unsupported_extension {
+        return Err(ProgramErrorCode::UnsupportedPaymentMint.into());
+    }
+
+    Ok(())
+}
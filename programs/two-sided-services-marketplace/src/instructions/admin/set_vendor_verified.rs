@@ -0,0 +1,50 @@
+use std::ops::DerefMut;
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::ProgramErrorCode,
+    states::{ConfigAccount, VendorProfileAccount, CONFIG_SEED, VENDOR_PROFILE_SEED},
+};
+
+#[derive(Accounts)]
+pub struct SetVendorVerified<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [&CONFIG_SEED.as_bytes()],
+        bump = config_account.bump
+    )]
+    pub config_account: Account<'info, ConfigAccount>,
+    #[account(
+        mut,
+        seeds = [&VENDOR_PROFILE_SEED.as_bytes(), vendor_profile.vendor.as_ref()],
+        bump = vendor_profile.bump
+    )]
+    pub vendor_profile: Account<'info, VendorProfileAccount>,
+}
+
+#[event]
+pub struct SetVendorVerifiedEvent {
+    pub admin: Pubkey,
+    pub vendor: Pubkey,
+    pub is_verified: bool,
+}
+
+pub fn set_vendor_verified(ctx: Context<SetVendorVerified>, is_verified: bool) -> Result<()> {
+    // Ensure the caller is the admin
+    if ctx.accounts.admin.key() != ctx.accounts.config_account.admin {
+        return Err(ProgramErrorCode::Unauthorized.into());
+    }
+
+    let vendor_profile = ctx.accounts.vendor_profile.deref_mut();
+    vendor_profile.is_verified = is_verified;
+
+    emit!(SetVendorVerifiedEvent {
+        admin: ctx.accounts.admin.key(),
+        vendor: vendor_profile.vendor,
+        is_verified,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use std::ops::DerefMut;
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    constant::{MAX_PUBKEY_STRING_LENGTH, MAX_URI_LENGTH, URI_MINT_PLACEHOLDER},
+    error::ProgramErrorCode,
+    states::{ConfigAccount, CONFIG_SEED},
+};
+
+#[derive(Accounts)]
+pub struct UpdateDefaultUriTemplate<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [&CONFIG_SEED.as_bytes()],
+        bump = config_account.bump
+    )]
+    pub config_account: Account<'info, ConfigAccount>,
+}
+
+#[event]
+pub struct UpdateDefaultUriTemplateEvent {
+    pub admin: Pubkey,
+    pub old_default_uri_template: String,
+    pub new_default_uri_template: String,
+}
+
+pub fn update_default_uri_template(
+    ctx: Context<UpdateDefaultUriTemplate>,
+    new_default_uri_template: String,
+) -> Result<()> {
+    let config_account = ctx.accounts.config_account.deref_mut();
+
+    // Ensure the caller is the admin
+    if ctx.accounts.admin.key() != config_account.admin {
+        return Err(ProgramErrorCode::Unauthorized.into());
+    }
+
+    // Every placeholder grows by up to a full mint address when a service is created
+    let placeholders = new_default_uri_template
+        .matches(URI_MINT_PLACEHOLDER)
+        .count();
+    let max_expanded_length = new_default_uri_template.len()
+        + placeholders * (MAX_PUBKEY_STRING_LENGTH - URI_MINT_PLACEHOLDER.len());
+    if max_expanded_length > MAX_URI_LENGTH {
+        return Err(ProgramErrorCode::UriTooLong.into());
+    }
+
+    // Services already created keep the URI they were created with
+    let old_default_uri_template = std::mem::replace(
+        &mut config_account.default_uri_template,
+        new_default_uri_template.clone(),
+    );
+
+    emit!(UpdateDefaultUriTemplateEvent {
+        admin: ctx.accounts.admin.key(),
+        old_default_uri_template,
+        new_default_uri_template,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use std::ops::DerefMut;
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::ProgramErrorCode,
+    helper::validate_fee_basis_points,
+    states::{ConfigAccount, CONFIG_SEED},
+};
+
+#[derive(Accounts)]
+pub struct UpdateMaxRoyalty<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [&CONFIG_SEED.as_bytes()],
+        bump = config_account.bump
+    )]
+    pub config_account: Account<'info, ConfigAccount>,
+}
+
+#[event]
+pub struct UpdateMaxRoyaltyEvent {
+    pub admin: Pubkey,
+    pub old_max_royalty_fee_basis_points: u16,
+    pub new_max_royalty_fee_basis_points: u16,
+}
+
+pub fn update_max_royalty(
+    ctx: Context<UpdateMaxRoyalty>,
+    new_max_royalty_fee_basis_points: u16,
+) -> Result<()> {
+    let config_account = ctx.accounts.config_account.deref_mut();
+
+    // Ensure the caller is the admin
+    if ctx.accounts.admin.key() != config_account.admin {
+        return Err(ProgramErrorCode::Unauthorized.into());
+    }
+
+    // The default royalty must stay within the maximum
+    if config_account.royalty_fee_basis_points > new_max_royalty_fee_basis_points {
+        return Err(ProgramErrorCode::RoyaltyExceedsMaximum.into());
+    }
+
+    validate_fee_basis_points(
+        new_max_royalty_fee_basis_points,
+        config_account.protocol_fee_basis_points,
+    )?;
+
+    // Services already listed keep their royalty
+    let old_max_royalty_fee_basis_points = config_account.max_royalty_fee_basis_points;
+    config_account.max_royalty_fee_basis_points = new_max_royalty_fee_basis_points;
+
+    emit!(UpdateMaxRoyaltyEvent {
+        admin: ctx.accounts.admin.key(),
+        old_max_royalty_fee_basis_points,
+        new_max_royalty_fee_basis_points,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use std::ops::DerefMut;
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::ProgramErrorCode,
+    states::{ConfigAccount, CONFIG_SEED},
+};
+
+#[derive(Accounts)]
+pub struct UpdateOrderRefundTimeout<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [&CONFIG_SEED.as_bytes()],
+        bump = config_account.bump
+    )]
+    pub config_account: Account<'info, ConfigAccount>,
+}
+
+#[event]
+pub struct UpdateOrderRefundTimeoutEvent {
+    pub admin: Pubkey,
+    pub old_order_refund_timeout: i64,
+    pub new_order_refund_timeout: i64,
+}
+
+pub fn update_order_refund_timeout(
+    ctx: Context<UpdateOrderRefundTimeout>,
+    new_order_refund_timeout: i64,
+) -> Result<()> {
+    let config_account = ctx.accounts.config_account.deref_mut();
+
+    // Ensure the caller is the admin
+    if ctx.accounts.admin.key() != config_account.admin {
+        return Err(ProgramErrorCode::Unauthorized.into());
+    }
+
+    if new_order_refund_timeout <= 0 {
+        return Err(ProgramErrorCode::InvalidTimeout.into());
+    }
+
+    // Orders already placed keep the timeout they were placed with
+    let old_order_refund_timeout = config_account.order_refund_timeout;
+    config_account.order_refund_timeout = new_order_refund_timeout;
+
+    emit!(UpdateOrderRefundTimeoutEvent {
+        admin: ctx.accounts.admin.key(),
+        old_order_refund_timeout,
+        new_order_refund_timeout,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use std::ops::DerefMut;
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::ProgramErrorCode,
+    helper::validate_fee_basis_points,
+    states::{ConfigAccount, CONFIG_SEED},
+};
+
+#[derive(Accounts)]
+pub struct UpdateProtocolFee<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [&CONFIG_SEED.as_bytes()],
+        bump = config_account.bump
+    )]
+    pub config_account: Account<'info, ConfigAccount>,
+}
+
+#[event]
+pub struct UpdateProtocolFeeEvent {
+    pub admin: Pubkey,
+    pub old_protocol_fee_basis_points: u16,
+    pub new_protocol_fee_basis_points: u16,
+}
+
+pub fn update_protocol_fee(
+    ctx: Context<UpdateProtocolFee>,
+    new_protocol_fee_basis_points: u16,
+) -> Result<()> {
+    let config_account = ctx.accounts.config_account.deref_mut();
+
+    // Ensure the caller is the admin
+    if ctx.accounts.admin.key() != config_account.admin {
+        return Err(ProgramErrorCode::Unauthorized.into());
+    }
+
+    validate_fee_basis_points(
+        config_account.max_royalty_fee_basis_points,
+        new_protocol_fee_basis_points,
+    )?;
+
+    // Update the protocol fee basis points
+    let old_protocol_fee_basis_points = config_account.protocol_fee_basis_points;
+    config_account.protocol_fee_basis_points = new_protocol_fee_basis_points;
+
+    emit!(UpdateProtocolFeeEvent {
+        admin: ctx.accounts.admin.key(),
+        old_protocol_fee_basis_points,
+        new_protocol_fee_basis_points,
+    });
+
+    Ok(())
+}
@@ -2,16 +2,30 @@ use std::ops::DerefMut;
 
 use anchor_lang::prelude::*;
 
-use crate::{error::ProgramErrorCode, states::ConfigAccount};
+use crate::{
+    error::ProgramErrorCode,
+    states::{ConfigAccount, CONFIG_SEED},
+};
 
 #[derive(Accounts)]
 pub struct UpdateRoyalty<'info> {
     #[account(mut)]
     pub admin: Signer<'info>,
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [&CONFIG_SEED.as_bytes()],
+        bump = config_account.bump
+    )]
     pub config_account: Account<'info, ConfigAccount>,
 }
 
+#[event]
+pub struct UpdateRoyaltyEvent {
+    pub admin: Pubkey,
+    pub old_royalty_fee_basis_points: u16,
+    pub new_royalty_fee_basis_points: u16,
+}
+
 pub fn update_royalty(
     ctx: Context<UpdateRoyalty>,
     new_royalty_fee_basis_points: u16,
@@ -23,8 +37,19 @@ pub fn update_royalty(
         return Err(ProgramErrorCode::Unauthorized.into());
     }
 
-    // Update the royalty fee basis points
+    if new_royalty_fee_basis_points > config_account.max_royalty_fee_basis_points {
+        return Err(ProgramErrorCode::RoyaltyExceedsMaximum.into());
+    }
+
+    // Update the default royalty fee basis points, used by services created without their own royalty
+    let old_royalty_fee_basis_points = config_account.royalty_fee_basis_points;
     config_account.royalty_fee_basis_points = new_royalty_fee_basis_points;
 
+    emit!(UpdateRoyaltyEvent {
+        admin: ctx.accounts.admin.key(),
+        old_royalty_fee_basis_points,
+        new_royalty_fee_basis_points,
+    });
+
     Ok(())
 }
@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    error::ProgramErrorCode,
+    helper::{optional_account_info, token_payment, transfer_payment},
+    states::{ConfigAccount, TreasuryAccount, CONFIG_SEED, TREASURY_SEED},
+};
+
+#[derive(Accounts)]
+pub struct WithdrawTreasury<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    /// CHECK: This account only receives lamports.
+    #[account(mut)]
+    pub recipient: AccountInfo<'info>,
+    #[account(
+        seeds = [&CONFIG_SEED.as_bytes()],
+        bump = config_account.bump
+    )]
+    pub config_account: Account<'info, ConfigAccount>,
+    #[account(
+        mut,
+        seeds = [&TREASURY_SEED.as_bytes()],
+        bump = treasury_account.bump
+    )]
+    pub treasury_account: Account<'info, TreasuryAccount>,
+
+    // The accounts below are only needed to withdraw fees collected in an SPL token
+    pub payment_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = treasury_account,
+        associated_token::token_program = token_program
+    )]
+    pub treasury_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = recipient,
+        token::token_program = token_program
+    )]
+    pub recipient_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+#[event]
+pub struct WithdrawTreasuryEvent {
+    pub admin: Pubkey,
+    pub recipient: Pubkey,
+    pub payment_mint: Option<Pubkey>,
+    pub amount: u64,
+}
+
+pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+    // Ensure the caller is the admin
+    if ctx.accounts.admin.key() != ctx.accounts.config_account.admin {
+        return Err(ProgramErrorCode::Unauthorized.into());
+    }
+
+    let token_payment = token_payment(&ctx.accounts.payment_mint, &ctx.accounts.token_program)?;
+    let treasury_info = ctx.accounts.treasury_account.to_account_info();
+
+    let available_amount = match &ctx.accounts.treasury_token_account {
+        Some(treasury_token_account) if token_payment.is_some() => treasury_token_account.amount,
+        // Keep the treasury rent exempt
+        _ => {
+            let rent_exempt_lamports = Rent::get()?.minimum_balance(treasury_info.data_len());
+            treasury_info.lamports().saturating_sub(rent_exempt_lamports)
+        }
+    };
+
+    if amount > available_amount {
+        return Err(ProgramErrorCode::InsufficientTreasuryBalance.into());
+    }
+
+    let signer: &[&[&[u8]]] = &[&[
+        TREASURY_SEED.as_bytes(),
+        &[ctx.accounts.treasury_account.bump],
+    ]];
+
+    transfer_payment(
+        treasury_info,
+        optional_account_info(&ctx.accounts.treasury_token_account),
+        ctx.accounts.recipient.to_account_info(),
+        optional_account_info(&ctx.accounts.recipient_token_account),
+        token_payment.as_ref(),
+        amount,
+        Some(signer),
+    )?;
+
+    emit!(WithdrawTreasuryEvent {
+        admin: ctx.accounts.admin.key(),
+        recipient: ctx.accounts.recipient.key(),
+        payment_mint: ctx.accounts.payment_mint.as_ref().map(|mint| mint.key()),
+        amount,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,238 @@
+use std::ops::DerefMut;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    error::ProgramErrorCode,
+    helper::{
+        close_token_account, optional_account_info, settle_sale, token_payment, SaleAmounts,
+        SalePayment,
+    },
+    states::{
+        ConfigAccount, OrderAccount, PurchaseReceiptAccount, ServiceAccount, TreasuryAccount,
+        VendorProfileAccount, ORDER_SEED, RECEIPT_SEED, SERVICE_SEED, TREASURY_SEED,
+        VENDOR_PROFILE_SEED,
+    },
+};
+
+#[derive(Accounts)]
+pub struct ApproveMilestoneRelease<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    /// CHECK: This account is the vendor of the order and is used to send lamports.
+    #[account(
+        mut,
+        constraint = vendor.key() == order_account.vendor @ProgramErrorCode::InvalidCurrentVendor
+    )]
+    pub vendor: AccountInfo<'info>,
+    pub config_account: Account<'info, ConfigAccount>,
+    #[account(
+        mut,
+        seeds = [&TREASURY_SEED.as_bytes()],
+        bump = treasury_account.bump
+    )]
+    pub treasury_account: Account<'info, TreasuryAccount>,
+    // Closed once the last milestone is released
+    #[account(
+        mut,
+        seeds = [&ORDER_SEED.as_bytes(), order_account.nft_mint.as_ref(), buyer.key().as_ref()],
+        bump = order_account.bump,
+        constraint = order_account.buyer == buyer.key() @ProgramErrorCode::InvalidBuyer,
+        constraint = !order_account.is_disputed @ProgramErrorCode::OrderDisputed,
+        constraint = order_account.release_requested @ProgramErrorCode::MilestoneReleaseNotRequested,
+        constraint = order_account.payment_mint == payment_mint.as_ref().map(|mint| mint.key()) @ProgramErrorCode::InvalidPaymentMint
+    )]
+    pub order_account: Account<'info, OrderAccount>,
+    #[account(
+        mut,
+        seeds = [&SERVICE_SEED.as_bytes(), order_account.nft_mint.as_ref()],
+        bump = service_account.bump
+    )]
+    pub service_account: Account<'info, ServiceAccount>,
+    // Proves the purchase when the buyer submits a review, only needed for the last milestone
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = PurchaseReceiptAccount::INIT_SPACE,
+        seeds = [&RECEIPT_SEED.as_bytes(), order_account.nft_mint.as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub receipt_account: Option<Box<Account<'info, PurchaseReceiptAccount>>>,
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = VendorProfileAccount::INIT_SPACE,
+        seeds = [&VENDOR_PROFILE_SEED.as_bytes(), vendor.key().as_ref()],
+        bump
+    )]
+    pub vendor_profile: Box<Account<'info, VendorProfileAccount>>,
+
+    // The accounts below are only needed when the order is paid in an SPL token
+    pub payment_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = order_account,
+        associated_token::token_program = token_program
+    )]
+    pub order_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = vendor,
+        token::token_program = token_program
+    )]
+    pub vendor_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        associated_token::mint = payment_mint,
+        associated_token::authority = treasury_account,
+        associated_token::token_program = token_program
+    )]
+    pub treasury_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct MilestoneReleasedEvent {
+    pub buyer: Pubkey,
+    pub vendor: Pubkey,
+    pub nft_mint: Pubkey,
+    pub milestone_index: u8,
+    pub amount: u64,
+    pub royalty_amount: u64,
+    pub protocol_fee_amount: u64,
+    pub remaining_amount: u64,
+    pub is_completed: bool,
+}
+
+// Royalty recipients are passed as remaining accounts, see `pay_royalties`
+pub fn approve_milestone_release<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, ApproveMilestoneRelease<'info>>,
+) -> Result<()> {
+    let accounts = &ctx.accounts;
+    let service_account = &accounts.service_account;
+    let order_account = &accounts.order_account;
+    let token_payment = token_payment(&accounts.payment_mint, &accounts.token_program)?;
+    let token_program = token_payment
+        .as_ref()
+        .map(|token_payment| token_payment.token_program.to_account_info());
+    let order_token_account = optional_account_info(&accounts.order_token_account);
+
+    let nft_mint = order_account.nft_mint;
+    let buyer = order_account.buyer;
+    let signer: &[&[&[u8]]] = &[&[
+        ORDER_SEED.as_bytes(),
+        nft_mint.as_ref(),
+        buyer.as_ref(),
+        &[order_account.bump],
+    ]];
+
+    let milestone_index = order_account.released_milestones;
+    let amount = order_account
+        .next_milestone_amount()
+        .ok_or(ProgramErrorCode::NoMilestoneToRelease)?;
+    let is_completed = milestone_index as usize + 1 == order_account.milestone_amounts.len();
+
+    let SaleAmounts {
+        royalty_amount,
+        protocol_fee_amount,
+        remaining_amount,
+    } = settle_sale(
+        &SalePayment {
+            from: order_account.to_account_info(),
+            from_token_account: order_token_account.clone(),
+            vendor: accounts.vendor.to_account_info(),
+            vendor_token_account: optional_account_info(&accounts.vendor_token_account),
+            treasury: accounts.treasury_account.to_account_info(),
+            treasury_token_account: optional_account_info(&accounts.treasury_token_account),
+            token_payment,
+            pda_signer_seeds: Some(signer),
+        },
+        amount,
+        accounts.config_account.protocol_fee_basis_points,
+        service_account.resale_royalty_basis_points(),
+        &service_account.creators,
+        ctx.remaining_accounts,
+    )?;
+
+    let order_account = ctx.accounts.order_account.deref_mut();
+    order_account.released_milestones = milestone_index + 1;
+    order_account.released_amount = order_account
+        .released_amount
+        .checked_add(amount)
+        .ok_or(ProgramErrorCode::Overflow)?;
+    order_account.release_requested = false;
+
+    // Created on any release, so it is initialized even before the sale is recorded
+    let vendor = ctx.accounts.vendor.key();
+    ctx.accounts
+        .vendor_profile
+        .init_if_needed(ctx.bumps.vendor_profile, vendor);
+
+    // The last release completes the order like confirm_delivery
+    if is_completed {
+        if let (Some(token_program), Some(order_token_account)) =
+            (token_program, order_token_account)
+        {
+            close_token_account(
+                order_token_account,
+                ctx.accounts.buyer.to_account_info(),
+                ctx.accounts.order_account.to_account_info(),
+                token_program,
+                signer,
+            )?;
+        }
+
+        let payment_mint = ctx.accounts.order_account.payment_mint;
+        let price = ctx.accounts.order_account.amount;
+
+        ctx.accounts
+            .receipt_account
+            .as_mut()
+            .ok_or(ProgramErrorCode::MissingReceiptAccount)?
+            .record(
+                ctx.bumps.receipt_account.ok_or(ProgramErrorCode::MissingReceiptAccount)?,
+                buyer,
+                ctx.accounts.vendor.key(),
+                nft_mint,
+                payment_mint,
+                price,
+            )?;
+
+        ctx.accounts.vendor_profile.record_sale(payment_mint, price)?;
+
+        let service_account = ctx.accounts.service_account.deref_mut();
+        service_account.change_vendor(buyer);
+        service_account.open_orders = service_account
+            .open_orders
+            .checked_sub(1)
+            .ok_or(ProgramErrorCode::Overflow)?;
+
+        ctx.accounts
+            .order_account
+            .close(ctx.accounts.buyer.to_account_info())?;
+    }
+
+    emit!(MilestoneReleasedEvent {
+        buyer,
+        vendor: ctx.accounts.vendor.key(),
+        nft_mint,
+        milestone_index,
+        amount,
+        royalty_amount,
+        protocol_fee_amount,
+        remaining_amount,
+        is_completed,
+    });
+
+    Ok(())
+}
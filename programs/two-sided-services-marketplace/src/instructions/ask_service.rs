@@ -1,10 +1,15 @@
 use std::ops::DerefMut;
 
 use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
 
 use crate::{
-    helper::send_lamports,
-    states::{ConfigAccount, ServiceAccount},
+    error::ProgramErrorCode,
+    helper::{optional_account_info, token_payment, transfer_payment, validate_expiry},
+    states::{ConfigAccount, ServiceAccount, SERVICE_SEED},
     AskAccount, ASK_SEED,
 };
 
@@ -12,20 +17,54 @@ use crate::{
 pub struct AskService<'info> {
     #[account(mut)]
     pub asker: Signer<'info>,
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = !config_account.paused @ProgramErrorCode::MarketplacePaused
+    )]
     pub config_account: Account<'info, ConfigAccount>,
     /// CHECK: Mint account.
     #[account(mut)]
     pub nft_mint: AccountInfo<'info>,
     #[account(
-      init_if_needed,
+        mut,
+        seeds = [&SERVICE_SEED.as_bytes(), nft_mint.key().as_ref()],
+        bump = service_account.bump,
+        constraint = service_account.is_listed @ProgramErrorCode::ServiceNotListed,
+        constraint = service_account.is_in_custody @ProgramErrorCode::ServiceNotInCustody,
+        constraint = service_account.unit_mint.is_none() @ProgramErrorCode::InventoryAlreadyEnabled,
+        constraint = service_account.payment_mint == payment_mint.as_ref().map(|mint| mint.key()) @ProgramErrorCode::InvalidPaymentMint
+    )]
+    pub service_account: Account<'info, ServiceAccount>,
+    // Each asker holds their own bid on a service, an existing bid is changed with update_ask_price
+    #[account(
+      init,
       payer = asker,
-      space = ServiceAccount::INIT_SPACE,
-      seeds = [&ASK_SEED.as_bytes(), nft_mint.key().as_ref()],
+      space = AskAccount::INIT_SPACE,
+      seeds = [&ASK_SEED.as_bytes(), nft_mint.key().as_ref(), asker.key().as_ref()],
       bump
   )]
     pub ask_account: Account<'info, AskAccount>,
 
+    // The accounts below are only needed when the service is paid in an SPL token
+    pub payment_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = asker,
+        token::token_program = token_program
+    )]
+    pub asker_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        init_if_needed,
+        payer = asker,
+        associated_token::mint = payment_mint,
+        associated_token::authority = ask_account,
+        associated_token::token_program = token_program
+    )]
+    pub ask_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+
     pub rent: Sysvar<'info, Rent>,
 
     pub system_program: Program<'info, System>,
@@ -35,28 +74,47 @@ pub struct AskService<'info> {
 pub struct AskServiceEvent {
     pub asker: Pubkey,
     pub nft_mint: Pubkey,
+    pub payment_mint: Option<Pubkey>,
     pub ask_price: u64,
+    pub expires_at: Option<i64>,
 }
 
-pub fn ask_service(ctx: Context<AskService>, ask_price: u64) -> Result<()> {
+pub fn ask_service(ctx: Context<AskService>, ask_price: u64, expires_at: Option<i64>) -> Result<()> {
+    validate_expiry(expires_at)?;
+
+    let service_account = ctx.accounts.service_account.deref_mut();
+    service_account.open_asks = service_account
+        .open_asks
+        .checked_add(1)
+        .ok_or(ProgramErrorCode::Overflow)?;
+
+    let payment_mint = service_account.payment_mint;
     let ask_account = ctx.accounts.ask_account.deref_mut();
 
     ask_account.bump = ctx.bumps.ask_account;
     ask_account.ask_price = ask_price;
     ask_account.asker = ctx.accounts.asker.key();
     ask_account.nft_mint = ctx.accounts.nft_mint.key();
+    ask_account.payment_mint = payment_mint;
+    ask_account.expires_at = expires_at;
 
-    //send lamports to program
-    send_lamports(
+    //send the ask price to the program
+    transfer_payment(
         ctx.accounts.asker.to_account_info(),
+        optional_account_info(&ctx.accounts.asker_token_account),
         ctx.accounts.ask_account.to_account_info(),
+        optional_account_info(&ctx.accounts.ask_token_account),
+        token_payment(&ctx.accounts.payment_mint, &ctx.accounts.token_program)?.as_ref(),
         ask_price,
+        None,
     )?;
 
     emit!(AskServiceEvent {
         asker: ctx.accounts.asker.key(),
         nft_mint: ctx.accounts.nft_mint.key(),
+        payment_mint,
         ask_price,
+        expires_at,
     });
 
     Ok(())
@@ -1,12 +1,21 @@
 use std::ops::DerefMut;
 
 use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_2022,
+    token_interface::{Mint, Token2022, TokenAccount, TokenInterface},
+};
 
 use crate::{
-    constant::MAX_FEE_BASIS_POINTS,
     error::ProgramErrorCode,
-    helper::send_lamports,
-    states::{ConfigAccount, ServiceAccount},
+    helper::{
+        has_expired, optional_account_info, settle_sale, token_payment, SaleAmounts, SalePayment,
+    },
+    states::{
+        ConfigAccount, PurchaseReceiptAccount, ServiceAccount, TreasuryAccount,
+        VendorProfileAccount, CONFIG_SEED, RECEIPT_SEED, TREASURY_SEED, VENDOR_PROFILE_SEED,
+    },
 };
 
 #[derive(Accounts)]
@@ -14,19 +23,93 @@ pub struct BuyService<'info> {
     #[account(mut)]
     pub buyer: Signer<'info>,
     /// CHECK: This account is the current vendor and is used to send lamports.
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = current_vendor.key() != buyer.key() @ProgramErrorCode::CannotBuyOwnService
+    )]
     pub current_vendor: AccountInfo<'info>,
-    /// CHECK: This account is the original vendor and is used to send lamports.
-    #[account(mut)]
+    /// CHECK: This account is the original vendor, royalties go to the creators of the service.
     pub original_vendor: AccountInfo<'info>,
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = !config_account.paused @ProgramErrorCode::MarketplacePaused
+    )]
     pub config_account: Account<'info, ConfigAccount>,
+    #[account(
+        mut,
+        seeds = [&TREASURY_SEED.as_bytes()],
+        bump = treasury_account.bump
+    )]
+    pub treasury_account: Account<'info, TreasuryAccount>,
     #[account(
         mut,
         constraint = service_account.original_vendor == original_vendor.key(),
-        constraint = service_account.current_vendor == current_vendor.key()
+        constraint = service_account.current_vendor == current_vendor.key(),
+        constraint = service_account.is_listed @ProgramErrorCode::ServiceNotListed,
+        constraint = service_account.is_in_custody @ProgramErrorCode::ServiceNotInCustody,
+        constraint = service_account.open_orders == 0 @ProgramErrorCode::ServiceHasOpenOrder,
+        constraint = !service_account.is_in_auction @ProgramErrorCode::ServiceInAuction,
+        constraint = service_account.payment_mint == payment_mint.as_ref().map(|mint| mint.key()) @ProgramErrorCode::InvalidPaymentMint,
+        constraint = service_account.unit_mint == unit_mint.as_ref().map(|mint| mint.key()) @ProgramErrorCode::InvalidUnitMint
     )]
     pub service_account: Account<'info, ServiceAccount>,
+    // Proves the purchase when the buyer submits a review
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = PurchaseReceiptAccount::INIT_SPACE,
+        seeds = [&RECEIPT_SEED.as_bytes(), service_account.nft_mint.as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub receipt_account: Box<Account<'info, PurchaseReceiptAccount>>,
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = VendorProfileAccount::INIT_SPACE,
+        seeds = [&VENDOR_PROFILE_SEED.as_bytes(), current_vendor.key().as_ref()],
+        bump
+    )]
+    pub vendor_profile: Box<Account<'info, VendorProfileAccount>>,
+
+    // The accounts below are only needed when the service is paid in an SPL token
+    pub payment_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = buyer,
+        token::token_program = token_program
+    )]
+    pub buyer_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = current_vendor,
+        token::token_program = token_program
+    )]
+    pub current_vendor_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        associated_token::mint = payment_mint,
+        associated_token::authority = treasury_account,
+        associated_token::token_program = token_program
+    )]
+    pub treasury_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+
+    // The accounts below are only needed when the service is sold in units
+    #[account(mut)]
+    pub unit_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        associated_token::mint = unit_mint,
+        associated_token::authority = buyer,
+        associated_token::token_program = unit_token_program
+    )]
+    pub buyer_unit_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub unit_token_program: Option<Program<'info, Token2022>>,
 
     pub rent: Sysvar<'info, Rent>,
 
@@ -38,67 +121,130 @@ pub struct BuyServiceEvent {
     pub buyer: Pubkey,
     pub current_vendor: Pubkey,
     pub original_vendor: Pubkey,
+    pub payment_mint: Option<Pubkey>,
+    pub unit_mint: Option<Pubkey>,
     pub price: u64,
     pub royalty_amount: u64,
+    pub protocol_fee_amount: u64,
     pub remaining_amount: u64,
 }
 
-pub fn buy_service(ctx: Context<BuyService>) -> Result<()> {
-    let service_account = ctx.accounts.service_account.deref_mut();
-    let config_account = &ctx.accounts.config_account;
-
-    let price = service_account.price;
-    let mut royalty_amount = 0;
-    let mut remaining_amount = price;
-
-    // If the current vendor is not the original vendor, proceed with royalties
-    let current_vendor = service_account.current_vendor;
-
-    if current_vendor != service_account.original_vendor {
-        let royalty_fee_basis_points = config_account.royalty_fee_basis_points as u128;
-
-        royalty_amount = (price as u128)
-            .checked_mul(royalty_fee_basis_points)
-            .ok_or(ProgramErrorCode::Overflow)?
-            .checked_div(MAX_FEE_BASIS_POINTS as u128)
-            .ok_or(ProgramErrorCode::DivideByZero)? as u64;
-        remaining_amount = price
-            .checked_sub(royalty_amount)
-            .ok_or(ProgramErrorCode::Overflow)?;
-
-        // Send royalties to the original vendor
-        send_lamports(
-            ctx.accounts.buyer.to_account_info(),
-            ctx.accounts.original_vendor.to_account_info(),
-            royalty_amount,
-        )?;
-
-        // Send the remaining amount to the current vendor
-        send_lamports(
-            ctx.accounts.buyer.to_account_info(),
-            ctx.accounts.current_vendor.to_account_info(),
-            remaining_amount,
-        )?;
-    } else {
-        send_lamports(
-            ctx.accounts.buyer.to_account_info(),
-            ctx.accounts.current_vendor.to_account_info(),
-            price,
-        )?;
+// Royalty recipients are passed as remaining accounts, see `pay_royalties`
+pub fn buy_service<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, BuyService<'info>>,
+    max_price: u64,
+) -> Result<()> {
+    let accounts = &ctx.accounts;
+    let service_account = &accounts.service_account;
+    let config_account = &accounts.config_account;
+
+    if has_expired(service_account.expires_at)? {
+        return Err(ProgramErrorCode::ListingExpired.into());
     }
 
-    // Update the new vendor
-    service_account.current_vendor = ctx.accounts.buyer.key();
+    // The price follows a Dutch auction or may have been raised with update_service_price,
+    // the buyer caps what they are willing to pay
+    let price = service_account.current_price(Clock::get()?.unix_timestamp);
+    if price > max_price {
+        return Err(ProgramErrorCode::PriceAboveMaximum.into());
+    }
+
+    // Pay the protocol fee, the royalties if this is a resale and the current vendor
+    let SaleAmounts {
+        royalty_amount,
+        protocol_fee_amount,
+        remaining_amount,
+    } = settle_sale(
+        &SalePayment {
+            from: accounts.buyer.to_account_info(),
+            from_token_account: optional_account_info(&accounts.buyer_token_account),
+            vendor: accounts.current_vendor.to_account_info(),
+            vendor_token_account: optional_account_info(&accounts.current_vendor_token_account),
+            treasury: accounts.treasury_account.to_account_info(),
+            treasury_token_account: optional_account_info(&accounts.treasury_token_account),
+            token_payment: token_payment(&accounts.payment_mint, &accounts.token_program)?,
+            pda_signer_seeds: None,
+        },
+        price,
+        config_account.protocol_fee_basis_points,
+        service_account.resale_royalty_basis_points(),
+        &service_account.creators,
+        ctx.remaining_accounts,
+    )?;
+
+    let payment_mint = service_account.payment_mint;
+    let nft_mint = service_account.nft_mint;
+
+    ctx.accounts.receipt_account.record(
+        ctx.bumps.receipt_account,
+        ctx.accounts.buyer.key(),
+        ctx.accounts.current_vendor.key(),
+        nft_mint,
+        payment_mint,
+        price,
+    )?;
+
+    let vendor_profile = ctx.accounts.vendor_profile.deref_mut();
+    vendor_profile.init_if_needed(ctx.bumps.vendor_profile, ctx.accounts.current_vendor.key());
+    vendor_profile.record_sale(payment_mint, price)?;
+
+    let unit_mint = ctx.accounts.service_account.unit_mint;
+    match unit_mint {
+        // Sell one unit, the listing stays with the vendor
+        Some(_) => {
+            let service_account = ctx.accounts.service_account.deref_mut();
+            service_account.remaining_units = service_account
+                .remaining_units
+                .checked_sub(1)
+                .ok_or(ProgramErrorCode::ServiceSoldOut)?;
+
+            mint_unit(&ctx)?;
+        }
+        // Update the new vendor
+        None => {
+            let service_account = ctx.accounts.service_account.deref_mut();
+            service_account.change_vendor(ctx.accounts.buyer.key());
+        }
+    }
 
     // Emit the event
     emit!(BuyServiceEvent {
         buyer: ctx.accounts.buyer.key(),
         current_vendor: ctx.accounts.current_vendor.key(),
         original_vendor: ctx.accounts.original_vendor.key(),
+        payment_mint,
+        unit_mint,
         price,
         royalty_amount,
+        protocol_fee_amount,
         remaining_amount,
     });
 
     Ok(())
 }
+
+// The unit token is the buyer's proof of purchase for multi-unit services
+fn mint_unit(ctx: &Context<BuyService>) -> Result<()> {
+    let (Some(unit_mint), Some(buyer_unit_token_account), Some(unit_token_program)) = (
+        &ctx.accounts.unit_mint,
+        &ctx.accounts.buyer_unit_token_account,
+        &ctx.accounts.unit_token_program,
+    ) else {
+        return Err(ProgramErrorCode::MissingTokenAccount.into());
+    };
+
+    let signer: &[&[&[u8]]] = &[&[CONFIG_SEED.as_bytes(), &[ctx.accounts.config_account.bump]]];
+
+    token_2022::mint_to(
+        CpiContext::new_with_signer(
+            unit_token_program.to_account_info(),
+            token_2022::MintTo {
+                mint: unit_mint.to_account_info(),
+                to: buyer_unit_token_account.to_account_info(),
+                authority: ctx.accounts.config_account.to_account_info(),
+            },
+            signer,
+        ),
+        1,
+    )
+}
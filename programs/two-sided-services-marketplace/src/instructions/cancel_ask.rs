@@ -0,0 +1,111 @@
+use std::ops::DerefMut;
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    error::ProgramErrorCode,
+    helper::{close_token_account, optional_account_info, token_payment, transfer_payment},
+    states::{ServiceAccount, SERVICE_SEED},
+    AskAccount, ASK_SEED,
+};
+
+#[derive(Accounts)]
+pub struct CancelAsk<'info> {
+    #[account(mut)]
+    pub asker: Signer<'info>,
+    // Closing the ask returns both the escrowed ask price and the rent to the asker
+    #[account(
+        mut,
+        close = asker,
+        seeds = [&ASK_SEED.as_bytes(), ask_account.nft_mint.as_ref(), asker.key().as_ref()],
+        bump = ask_account.bump,
+        constraint = ask_account.asker == asker.key() @ProgramErrorCode::Unauthorized,
+        constraint = ask_account.payment_mint == payment_mint.as_ref().map(|mint| mint.key()) @ProgramErrorCode::InvalidPaymentMint
+    )]
+    pub ask_account: Account<'info, AskAccount>,
+    #[account(
+        mut,
+        seeds = [&SERVICE_SEED.as_bytes(), ask_account.nft_mint.as_ref()],
+        bump = service_account.bump
+    )]
+    pub service_account: Account<'info, ServiceAccount>,
+
+    // The accounts below are only needed when the ask is paid in an SPL token
+    pub payment_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = asker,
+        token::token_program = token_program
+    )]
+    pub asker_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = ask_account,
+        associated_token::token_program = token_program
+    )]
+    pub ask_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct AskCancelledEvent {
+    pub asker: Pubkey,
+    pub nft_mint: Pubkey,
+    pub refund_amount: u64,
+}
+
+pub fn cancel_ask(ctx: Context<CancelAsk>) -> Result<()> {
+    let service_account = ctx.accounts.service_account.deref_mut();
+    service_account.open_asks = service_account
+        .open_asks
+        .checked_sub(1)
+        .ok_or(ProgramErrorCode::Overflow)?;
+
+    let ask_account = &ctx.accounts.ask_account;
+
+    // Lamport escrow is refunded when the ask account is closed, token escrow is refunded here
+    if let Some(token_payment) =
+        token_payment(&ctx.accounts.payment_mint, &ctx.accounts.token_program)?
+    {
+        let nft_mint = ask_account.nft_mint;
+        let asker = ask_account.asker;
+        let signer: &[&[&[u8]]] = &[&[
+            ASK_SEED.as_bytes(),
+            nft_mint.as_ref(),
+            asker.as_ref(),
+            &[ask_account.bump],
+        ]];
+
+        transfer_payment(
+            ask_account.to_account_info(),
+            optional_account_info(&ctx.accounts.ask_token_account),
+            ctx.accounts.asker.to_account_info(),
+            optional_account_info(&ctx.accounts.asker_token_account),
+            Some(&token_payment),
+            ask_account.ask_price,
+            Some(signer),
+        )?;
+
+        close_token_account(
+            optional_account_info(&ctx.accounts.ask_token_account)
+                .ok_or(ProgramErrorCode::MissingTokenAccount)?,
+            ctx.accounts.asker.to_account_info(),
+            ask_account.to_account_info(),
+            token_payment.token_program.to_account_info(),
+            signer,
+        )?;
+    }
+
+    emit!(AskCancelledEvent {
+        asker: ctx.accounts.asker.key(),
+        nft_mint: ask_account.nft_mint,
+        refund_amount: ask_account.ask_price,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use std::ops::DerefMut;
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    error::ProgramErrorCode,
+    helper::{close_token_account, optional_account_info, token_payment},
+    states::{AuctionAccount, ServiceAccount, AUCTION_SEED, SERVICE_SEED},
+};
+
+#[derive(Accounts)]
+pub struct CancelAuction<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [&SERVICE_SEED.as_bytes(), auction_account.nft_mint.as_ref()],
+        bump = service_account.bump
+    )]
+    pub service_account: Account<'info, ServiceAccount>,
+    // Only an auction without bids can be cancelled, bidders are never left without the service
+    #[account(
+        mut,
+        close = seller,
+        seeds = [&AUCTION_SEED.as_bytes(), auction_account.nft_mint.as_ref()],
+        bump = auction_account.bump,
+        constraint = auction_account.seller == seller.key() @ProgramErrorCode::Unauthorized,
+        constraint = auction_account.highest_bidder.is_none() @ProgramErrorCode::AuctionHasBids,
+        constraint = auction_account.payment_mint == payment_mint.as_ref().map(|mint| mint.key()) @ProgramErrorCode::InvalidPaymentMint
+    )]
+    pub auction_account: Account<'info, AuctionAccount>,
+
+    // The accounts below are only needed when the service is paid in an SPL token
+    pub payment_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = auction_account,
+        associated_token::token_program = token_program
+    )]
+    pub auction_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+#[event]
+pub struct CancelAuctionEvent {
+    pub seller: Pubkey,
+    pub nft_mint: Pubkey,
+}
+
+pub fn cancel_auction(ctx: Context<CancelAuction>) -> Result<()> {
+    let auction_account = &ctx.accounts.auction_account;
+    let nft_mint = auction_account.nft_mint;
+
+    if let Some(token_payment) =
+        token_payment(&ctx.accounts.payment_mint, &ctx.accounts.token_program)?
+    {
+        let signer: &[&[&[u8]]] = &[&[
+            AUCTION_SEED.as_bytes(),
+            nft_mint.as_ref(),
+            &[auction_account.bump],
+        ]];
+
+        close_token_account(
+            optional_account_info(&ctx.accounts.auction_token_account)
+                .ok_or(ProgramErrorCode::MissingTokenAccount)?,
+            ctx.accounts.seller.to_account_info(),
+            auction_account.to_account_info(),
+            token_payment.token_program.to_account_info(),
+            signer,
+        )?;
+    }
+
+    let service_account = ctx.accounts.service_account.deref_mut();
+    service_account.is_in_auction = false;
+
+    emit!(CancelAuctionEvent {
+        seller: ctx.accounts.seller.key(),
+        nft_mint,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,137 @@
+use std::ops::DerefMut;
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    constant::EXPIRED_ASK_CRANK_REWARD,
+    error::ProgramErrorCode,
+    helper::{
+        close_token_account, has_expired, optional_account_info, token_payment, transfer_payment,
+        withdraw_lamports,
+    },
+    states::{ServiceAccount, SERVICE_SEED},
+    AskAccount, ASK_SEED,
+};
+
+#[derive(Accounts)]
+pub struct CancelExpiredAsk<'info> {
+    // Anyone can crank an expired ask and is paid from its rent
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+    /// CHECK: This account is the asker and receives the escrow and the remaining rent.
+    #[account(mut)]
+    pub asker: AccountInfo<'info>,
+    #[account(
+        mut,
+        close = asker,
+        seeds = [&ASK_SEED.as_bytes(), ask_account.nft_mint.as_ref(), asker.key().as_ref()],
+        bump = ask_account.bump,
+        constraint = ask_account.asker == asker.key() @ProgramErrorCode::InvalidAsker,
+        constraint = ask_account.payment_mint == payment_mint.as_ref().map(|mint| mint.key()) @ProgramErrorCode::InvalidPaymentMint
+    )]
+    pub ask_account: Account<'info, AskAccount>,
+    #[account(
+        mut,
+        seeds = [&SERVICE_SEED.as_bytes(), ask_account.nft_mint.as_ref()],
+        bump = service_account.bump
+    )]
+    pub service_account: Account<'info, ServiceAccount>,
+
+    // The accounts below are only needed when the ask is paid in an SPL token
+    pub payment_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = asker,
+        token::token_program = token_program
+    )]
+    pub asker_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = ask_account,
+        associated_token::token_program = token_program
+    )]
+    pub ask_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct ExpiredAskCancelledEvent {
+    pub cranker: Pubkey,
+    pub asker: Pubkey,
+    pub nft_mint: Pubkey,
+    pub refund_amount: u64,
+    pub crank_reward: u64,
+}
+
+pub fn cancel_expired_ask(ctx: Context<CancelExpiredAsk>) -> Result<()> {
+    let ask_account = &ctx.accounts.ask_account;
+
+    if !has_expired(ask_account.expires_at)? {
+        return Err(ProgramErrorCode::AskNotExpired.into());
+    }
+
+    let nft_mint = ask_account.nft_mint;
+    let asker = ask_account.asker;
+    let signer: &[&[&[u8]]] = &[&[
+        ASK_SEED.as_bytes(),
+        nft_mint.as_ref(),
+        asker.as_ref(),
+        &[ask_account.bump],
+    ]];
+
+    // Lamport escrow is refunded when the ask account is closed, token escrow is refunded here
+    if let Some(token_payment) =
+        token_payment(&ctx.accounts.payment_mint, &ctx.accounts.token_program)?
+    {
+        transfer_payment(
+            ask_account.to_account_info(),
+            optional_account_info(&ctx.accounts.ask_token_account),
+            ctx.accounts.asker.to_account_info(),
+            optional_account_info(&ctx.accounts.asker_token_account),
+            Some(&token_payment),
+            ask_account.ask_price,
+            Some(signer),
+        )?;
+
+        close_token_account(
+            optional_account_info(&ctx.accounts.ask_token_account)
+                .ok_or(ProgramErrorCode::MissingTokenAccount)?,
+            ctx.accounts.asker.to_account_info(),
+            ask_account.to_account_info(),
+            token_payment.token_program.to_account_info(),
+            signer,
+        )?;
+    }
+
+    // The reward comes out of the ask rent, never out of the escrow
+    let rent = Rent::get()?.minimum_balance(AskAccount::INIT_SPACE);
+    let crank_reward = EXPIRED_ASK_CRANK_REWARD.min(rent);
+    withdraw_lamports(
+        &ask_account.to_account_info(),
+        &ctx.accounts.cranker.to_account_info(),
+        crank_reward,
+    )?;
+
+    let refund_amount = ask_account.ask_price;
+
+    let service_account = ctx.accounts.service_account.deref_mut();
+    service_account.open_asks = service_account
+        .open_asks
+        .checked_sub(1)
+        .ok_or(ProgramErrorCode::Overflow)?;
+
+    emit!(ExpiredAskCancelledEvent {
+        cranker: ctx.accounts.cranker.key(),
+        asker,
+        nft_mint,
+        refund_amount,
+        crank_reward,
+    });
+
+    Ok(())
+}
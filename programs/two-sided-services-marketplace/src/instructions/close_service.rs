@@ -0,0 +1,198 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id,
+    token_2022,
+    token_interface::{Mint, Token2022, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    error::ProgramErrorCode,
+    helper::{close_token_account, token_payment, transfer_payment, TokenPayment},
+    states::{ConfigAccount, ServiceAccount, CONFIG_SEED, SERVICE_SEED},
+    AskAccount, ASK_SEED,
+};
+
+#[derive(Accounts)]
+pub struct CloseService<'info> {
+    // The current vendor, or the original vendor of a soulbound service
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [&CONFIG_SEED.as_bytes()],
+        bump = config_account.bump
+    )]
+    pub config_account: Account<'info, ConfigAccount>,
+    #[account(
+        mut,
+        close = authority,
+        seeds = [&SERVICE_SEED.as_bytes(), nft_mint.key().as_ref()],
+        bump = service_account.bump,
+        constraint = service_account.is_in_custody @ProgramErrorCode::ServiceNotInCustody,
+        constraint = service_account.open_orders == 0 @ProgramErrorCode::ServiceHasOpenOrder,
+        constraint = !service_account.is_in_auction @ProgramErrorCode::ServiceInAuction,
+        constraint = service_account.payment_mint == payment_mint.as_ref().map(|mint| mint.key()) @ProgramErrorCode::InvalidPaymentMint
+    )]
+    pub service_account: Account<'info, ServiceAccount>,
+    #[account(mut)]
+    pub nft_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::mint = nft_mint,
+        associated_token::authority = config_account,
+        associated_token::token_program = token_program
+    )]
+    pub config_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token2022>,
+
+    // The accounts below are only needed to refund asks paid in an SPL token
+    pub payment_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    pub payment_token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct CloseServiceEvent {
+    pub authority: Pubkey,
+    pub nft_mint: Pubkey,
+    pub refunded_asks: u32,
+}
+
+// Open asks are passed as remaining accounts: [ask_account, asker] when paid in SOL and
+// [ask_account, asker, ask_token_account, asker_token_account] when paid in an SPL token
+pub fn close_service<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, CloseService<'info>>,
+) -> Result<()> {
+    let accounts = &ctx.accounts;
+    let service_account = &accounts.service_account;
+    let authority = accounts.authority.key();
+
+    let is_original_vendor_of_soulbound =
+        service_account.is_soulbound && authority == service_account.original_vendor;
+    if authority != service_account.current_vendor && !is_original_vendor_of_soulbound {
+        return Err(ProgramErrorCode::Unauthorized.into());
+    }
+
+    // Every ask on the service has to be refunded before it goes away
+    let token_payment = token_payment(&accounts.payment_mint, &accounts.payment_token_program)?;
+    let accounts_per_ask = if token_payment.is_some() { 4 } else { 2 };
+    let mut refunded_asks: u32 = 0;
+
+    for ask_accounts in ctx.remaining_accounts.chunks(accounts_per_ask) {
+        if ask_accounts.len() != accounts_per_ask {
+            return Err(ProgramErrorCode::MissingTokenAccount.into());
+        }
+        refund_ask(ask_accounts, service_account.nft_mint, token_payment.as_ref())?;
+        refunded_asks = refunded_asks
+            .checked_add(1)
+            .ok_or(ProgramErrorCode::Overflow)?;
+    }
+
+    if refunded_asks != service_account.open_asks {
+        return Err(ProgramErrorCode::ServiceHasOpenAsks.into());
+    }
+
+    let signer: &[&[&[u8]]] = &[&[CONFIG_SEED.as_bytes(), &[accounts.config_account.bump]]];
+
+    // Burn the NFT held in custody, then return the rent of its token account and mint
+    token_2022::burn(
+        CpiContext::new_with_signer(
+            accounts.token_program.to_account_info(),
+            token_2022::Burn {
+                mint: accounts.nft_mint.to_account_info(),
+                from: accounts.config_token_account.to_account_info(),
+                authority: accounts.config_account.to_account_info(),
+            },
+            signer,
+        ),
+        1,
+    )?;
+
+    close_token_account(
+        accounts.config_token_account.to_account_info(),
+        accounts.authority.to_account_info(),
+        accounts.config_account.to_account_info(),
+        accounts.token_program.to_account_info(),
+        signer,
+    )?;
+
+    close_token_account(
+        accounts.nft_mint.to_account_info(),
+        accounts.authority.to_account_info(),
+        accounts.config_account.to_account_info(),
+        accounts.token_program.to_account_info(),
+        signer,
+    )?;
+
+    emit!(CloseServiceEvent {
+        authority,
+        nft_mint: service_account.nft_mint,
+        refunded_asks,
+    });
+
+    Ok(())
+}
+
+fn refund_ask<'info>(
+    ask_accounts: &'info [AccountInfo<'info>],
+    nft_mint: Pubkey,
+    token_payment: Option<&TokenPayment<'_, 'info>>,
+) -> Result<()> {
+    let ask_account = Account::<AskAccount>::try_from(&ask_accounts[0])?;
+    let asker = &ask_accounts[1];
+
+    if ask_account.nft_mint != nft_mint {
+        return Err(ProgramErrorCode::InvalidNftMint.into());
+    }
+    if ask_account.asker != asker.key() {
+        return Err(ProgramErrorCode::InvalidAsker.into());
+    }
+
+    // Lamport escrow is refunded when the ask account is closed, token escrow is refunded here
+    if let Some(token_payment) = token_payment {
+        let ask_token_account = &ask_accounts[2];
+        let asker_token_account = &ask_accounts[3];
+
+        let expected_ask_token_account = get_associated_token_address_with_program_id(
+            &ask_account.key(),
+            &token_payment.mint.key(),
+            &token_payment.token_program.key(),
+        );
+        if ask_token_account.key() != expected_ask_token_account {
+            return Err(ProgramErrorCode::MissingTokenAccount.into());
+        }
+
+        let asker_token = InterfaceAccount::<TokenAccount>::try_from(asker_token_account)?;
+        if asker_token.owner != asker.key() || asker_token.mint != token_payment.mint.key() {
+            return Err(ProgramErrorCode::InvalidAsker.into());
+        }
+
+        let signer: &[&[&[u8]]] = &[&[
+            ASK_SEED.as_bytes(),
+            nft_mint.as_ref(),
+            ask_account.asker.as_ref(),
+            &[ask_account.bump],
+        ]];
+
+        transfer_payment(
+            ask_account.to_account_info(),
+            Some(ask_token_account.clone()),
+            asker.clone(),
+            Some(asker_token_account.clone()),
+            Some(token_payment),
+            ask_account.ask_price,
+            Some(signer),
+        )?;
+
+        close_token_account(
+            ask_token_account.clone(),
+            asker.clone(),
+            ask_account.to_account_info(),
+            token_payment.token_program.to_account_info(),
+            signer,
+        )?;
+    }
+
+    ask_account.close(asker.clone())
+}
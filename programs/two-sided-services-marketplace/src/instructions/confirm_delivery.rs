@@ -0,0 +1,211 @@
+use std::ops::DerefMut;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    error::ProgramErrorCode,
+    helper::{
+        close_token_account, optional_account_info, settle_sale, token_payment, SaleAmounts,
+        SalePayment,
+    },
+    states::{
+        ConfigAccount, OrderAccount, PurchaseReceiptAccount, ServiceAccount, TreasuryAccount,
+        VendorProfileAccount, ORDER_SEED, RECEIPT_SEED, SERVICE_SEED, TREASURY_SEED,
+        VENDOR_PROFILE_SEED,
+    },
+};
+
+#[derive(Accounts)]
+pub struct ConfirmDelivery<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    /// CHECK: This account is the vendor of the order and is used to send lamports.
+    #[account(
+        mut,
+        constraint = vendor.key() == order_account.vendor @ProgramErrorCode::InvalidCurrentVendor
+    )]
+    pub vendor: AccountInfo<'info>,
+    pub config_account: Account<'info, ConfigAccount>,
+    #[account(
+        mut,
+        seeds = [&TREASURY_SEED.as_bytes()],
+        bump = treasury_account.bump
+    )]
+    pub treasury_account: Account<'info, TreasuryAccount>,
+    #[account(
+        mut,
+        close = buyer,
+        seeds = [&ORDER_SEED.as_bytes(), order_account.nft_mint.as_ref(), buyer.key().as_ref()],
+        bump = order_account.bump,
+        constraint = order_account.buyer == buyer.key() @ProgramErrorCode::InvalidBuyer,
+        constraint = !order_account.is_disputed @ProgramErrorCode::OrderDisputed,
+        constraint = order_account.payment_mint == payment_mint.as_ref().map(|mint| mint.key()) @ProgramErrorCode::InvalidPaymentMint
+    )]
+    pub order_account: Account<'info, OrderAccount>,
+    #[account(
+        mut,
+        seeds = [&SERVICE_SEED.as_bytes(), order_account.nft_mint.as_ref()],
+        bump = service_account.bump
+    )]
+    pub service_account: Account<'info, ServiceAccount>,
+    // Proves the purchase when the buyer submits a review
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = PurchaseReceiptAccount::INIT_SPACE,
+        seeds = [&RECEIPT_SEED.as_bytes(), order_account.nft_mint.as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub receipt_account: Box<Account<'info, PurchaseReceiptAccount>>,
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = VendorProfileAccount::INIT_SPACE,
+        seeds = [&VENDOR_PROFILE_SEED.as_bytes(), vendor.key().as_ref()],
+        bump
+    )]
+    pub vendor_profile: Box<Account<'info, VendorProfileAccount>>,
+
+    // The accounts below are only needed when the order is paid in an SPL token
+    pub payment_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = order_account,
+        associated_token::token_program = token_program
+    )]
+    pub order_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = vendor,
+        token::token_program = token_program
+    )]
+    pub vendor_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        associated_token::mint = payment_mint,
+        associated_token::authority = treasury_account,
+        associated_token::token_program = token_program
+    )]
+    pub treasury_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct ConfirmDeliveryEvent {
+    pub buyer: Pubkey,
+    pub vendor: Pubkey,
+    pub nft_mint: Pubkey,
+    pub payment_mint: Option<Pubkey>,
+    pub amount: u64,
+    pub royalty_amount: u64,
+    pub protocol_fee_amount: u64,
+    pub remaining_amount: u64,
+}
+
+// Royalty recipients are passed as remaining accounts, see `pay_royalties`
+pub fn confirm_delivery<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, ConfirmDelivery<'info>>,
+) -> Result<()> {
+    let accounts = &ctx.accounts;
+    let service_account = &accounts.service_account;
+    let order_account = &accounts.order_account;
+    let token_payment = token_payment(&accounts.payment_mint, &accounts.token_program)?;
+    let token_program = token_payment
+        .as_ref()
+        .map(|token_payment| token_payment.token_program.to_account_info());
+    let order_token_account = optional_account_info(&accounts.order_token_account);
+
+    let nft_mint = order_account.nft_mint;
+    let buyer = order_account.buyer;
+    let signer: &[&[&[u8]]] = &[&[
+        ORDER_SEED.as_bytes(),
+        nft_mint.as_ref(),
+        buyer.as_ref(),
+        &[order_account.bump],
+    ]];
+
+    // Confirming delivery releases whatever milestones are still escrowed
+    let amount = order_account.remaining_amount()?;
+
+    // Release the escrow the same way an instant purchase is paid
+    let SaleAmounts {
+        royalty_amount,
+        protocol_fee_amount,
+        remaining_amount,
+    } = settle_sale(
+        &SalePayment {
+            from: order_account.to_account_info(),
+            from_token_account: order_token_account.clone(),
+            vendor: accounts.vendor.to_account_info(),
+            vendor_token_account: optional_account_info(&accounts.vendor_token_account),
+            treasury: accounts.treasury_account.to_account_info(),
+            treasury_token_account: optional_account_info(&accounts.treasury_token_account),
+            token_payment,
+            pda_signer_seeds: Some(signer),
+        },
+        amount,
+        accounts.config_account.protocol_fee_basis_points,
+        service_account.resale_royalty_basis_points(),
+        &service_account.creators,
+        ctx.remaining_accounts,
+    )?;
+
+    if let (Some(token_program), Some(order_token_account)) = (token_program, order_token_account)
+    {
+        close_token_account(
+            order_token_account,
+            accounts.buyer.to_account_info(),
+            order_account.to_account_info(),
+            token_program,
+            signer,
+        )?;
+    }
+
+    let payment_mint = order_account.payment_mint;
+    let price = order_account.amount;
+
+    // The completed order is recorded like an instant purchase, with a receipt and a sale
+    ctx.accounts.receipt_account.record(
+        ctx.bumps.receipt_account,
+        buyer,
+        ctx.accounts.vendor.key(),
+        nft_mint,
+        payment_mint,
+        price,
+    )?;
+
+    let vendor_profile = ctx.accounts.vendor_profile.deref_mut();
+    vendor_profile.init_if_needed(ctx.bumps.vendor_profile, ctx.accounts.vendor.key());
+    vendor_profile.record_sale(payment_mint, price)?;
+
+    // The buyer takes over the service once it is delivered
+    let service_account = ctx.accounts.service_account.deref_mut();
+    service_account.change_vendor(buyer);
+    service_account.open_orders = service_account
+        .open_orders
+        .checked_sub(1)
+        .ok_or(ProgramErrorCode::Overflow)?;
+
+    emit!(ConfirmDeliveryEvent {
+        buyer,
+        vendor: ctx.accounts.vendor.key(),
+        nft_mint,
+        payment_mint,
+        amount,
+        royalty_amount,
+        protocol_fee_amount,
+        remaining_amount,
+    });
+
+    Ok(())
+}
@@ -12,22 +12,32 @@ use anchor_spl::{
         spl_token_2022::{self, extension::ExtensionType, state::Mint},
     },
     token_interface::{
-        non_transferable_mint_initialize, spl_token_2022::instruction::AuthorityType,
-        spl_token_metadata_interface, NonTransferableMintInitialize, Token2022,
+        self, non_transferable_mint_initialize,
+        spl_token_metadata_interface::{self, state::TokenMetadata},
+        NonTransferableMintInitialize, Token2022,
     },
 };
 
 use crate::{
-    constant::METADATA_URI,
+    constant::{
+        MAX_AGREEMENTS, MAX_NAME_LENGTH, MAX_SYMBOL_LENGTH, MAX_URI_LENGTH, URI_MINT_PLACEHOLDER,
+    },
     error::ProgramErrorCode,
-    states::{ConfigAccount, ServiceAccount, ServiceAgreement, CONFIG_SEED, SERVICE_SEED},
+    helper::{validate_agreement, validate_creators, withdraw_excess_mint_lamports},
+    states::{
+        ConfigAccount, Creator, RatingStats, ServiceAccount, ServiceAgreement, ServiceMetadata,
+        VendorProfileAccount, CONFIG_SEED, SERVICE_SEED, VENDOR_PROFILE_SEED,
+    },
 };
 
 #[derive(Accounts)]
 pub struct ListService<'info> {
     #[account(mut)]
     pub vendor: Signer<'info>,
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = !config_account.paused @ProgramErrorCode::MarketplacePaused
+    )]
     pub config_account: Account<'info, ConfigAccount>,
     #[account(mut)]
     pub nft_mint: Signer<'info>,
@@ -42,6 +52,19 @@ pub struct ListService<'info> {
       bump
   )]
     pub service_account: Account<'info, ServiceAccount>,
+    #[account(
+        init_if_needed,
+        payer = vendor,
+        space = VendorProfileAccount::INIT_SPACE,
+        seeds = [&VENDOR_PROFILE_SEED.as_bytes(), vendor.key().as_ref()],
+        bump
+    )]
+    pub vendor_profile: Box<Account<'info, VendorProfileAccount>>,
+    // Leave empty to price the service in SOL
+    #[account(
+        constraint = config_account.payment_mints.contains(&payment_mint.key()) @ProgramErrorCode::PaymentMintNotAllowed
+    )]
+    pub payment_mint: Option<Box<InterfaceAccount<'info, token_interface::Mint>>>,
 
     pub token_program: Program<'info, Token2022>,
 
@@ -57,7 +80,75 @@ pub fn create_service(
     is_soulbound: bool,
     agreements: Vec<ServiceAgreement>,
     price: u64,
+    royalty_basis_points: Option<u16>,
+    creators: Vec<Creator>,
+    metadata: ServiceMetadata,
 ) -> Result<()> {
+    let config_account = &ctx.accounts.config_account;
+
+    if metadata.name.len() > MAX_NAME_LENGTH {
+        return Err(ProgramErrorCode::NameTooLong.into());
+    }
+    if metadata.symbol.len() > MAX_SYMBOL_LENGTH {
+        return Err(ProgramErrorCode::SymbolTooLong.into());
+    }
+
+    let uri = match metadata.uri {
+        Some(uri) => uri,
+        None => config_account
+            .default_uri_template
+            .replace(URI_MINT_PLACEHOLDER, &ctx.accounts.nft_mint.key().to_string()),
+    };
+    if uri.len() > MAX_URI_LENGTH {
+        return Err(ProgramErrorCode::UriTooLong.into());
+    }
+
+    // Services listed without their own royalty use the marketplace default
+    let royalty_basis_points =
+        royalty_basis_points.unwrap_or(config_account.royalty_fee_basis_points);
+
+    if royalty_basis_points > config_account.max_royalty_fee_basis_points {
+        return Err(ProgramErrorCode::RoyaltyExceedsMaximum.into());
+    }
+
+    // Without an explicit split the whole royalty goes to the vendor
+    let creators = if creators.is_empty() {
+        vec![Creator {
+            address: ctx.accounts.vendor.key(),
+            share: 100,
+        }]
+    } else {
+        creators
+    };
+    validate_creators(&creators)?;
+
+    // Every agreement is a milestone, together they make up the price
+    if agreements.len() > MAX_AGREEMENTS {
+        return Err(ProgramErrorCode::TooManyAgreements.into());
+    }
+
+    // Agreements are metadata keys, a repeated title would overwrite the previous one
+    for (index, agreement) in agreements.iter().enumerate() {
+        validate_agreement(&agreement.title, &agreement.details)?;
+
+        if agreements[..index]
+            .iter()
+            .any(|other| other.title == agreement.title)
+        {
+            return Err(ProgramErrorCode::DuplicateAgreementTitle.into());
+        }
+    }
+
+    let milestone_amounts: Vec<u64> = agreements.iter().map(|agreement| agreement.amount).collect();
+    let milestone_total = milestone_amounts
+        .iter()
+        .try_fold(0u64, |total, amount| total.checked_add(*amount))
+        .ok_or(ProgramErrorCode::Overflow)?;
+
+    if !milestone_amounts.is_empty() && milestone_total != price {
+        return Err(ProgramErrorCode::InvalidMilestoneAmounts.into());
+    }
+
     let service_account = ctx.accounts.service_account.deref_mut();
 
     service_account.bump = ctx.bumps.service_account;
@@ -66,8 +157,37 @@ pub fn create_service(
     service_account.original_vendor = ctx.accounts.vendor.key();
     service_account.nft_mint = ctx.accounts.nft_mint.key();
     service_account.price = price;
+    service_account.is_listed = true;
+    service_account.payment_mint = ctx.accounts.payment_mint.as_ref().map(|mint| mint.key());
+    service_account.royalty_basis_points = royalty_basis_points;
+    service_account.creators = creators;
+    service_account.is_in_custody = true;
+    service_account.open_orders = 0;
+    service_account.milestone_amounts = milestone_amounts;
+    service_account.rating = RatingStats::default();
+    service_account.open_asks = 0;
+    service_account.expires_at = None;
+    service_account.is_in_auction = false;
+    service_account.dutch_auction = None;
+    service_account.unit_mint = None;
+    service_account.capacity = 0;
+    service_account.remaining_units = 0;
+
+    let vendor_profile = ctx.accounts.vendor_profile.deref_mut();
+    vendor_profile.init_if_needed(ctx.bumps.vendor_profile, ctx.accounts.vendor.key());
+    vendor_profile.service_count = vendor_profile
+        .service_count
+        .checked_add(1)
+        .ok_or(ProgramErrorCode::Overflow)?;
 
-    initialize_mint_and_metadata(&ctx, is_soulbound, agreements)?;
+    initialize_mint_and_metadata(
+        &ctx,
+        is_soulbound,
+        metadata.name,
+        metadata.symbol,
+        uri,
+        agreements,
+    )?;
 
     Ok(())
 }
@@ -75,15 +195,34 @@ pub fn create_service(
 pub fn initialize_mint_and_metadata(
     ctx: &Context<ListService>,
     is_soulbound: bool,
+    name: String,
+    symbol: String,
+    uri: String,
     agreements: Vec<ServiceAgreement>,
 ) -> Result<()> {
-    let space =
-        match ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::MetadataPointer]) {
-            Ok(space) => space,
-            Err(_) => return err!(ProgramErrorCode::InvalidMintAccountSpace),
-        };
+    let mut extensions = vec![ExtensionType::MetadataPointer, ExtensionType::MintCloseAuthority];
+    if is_soulbound {
+        extensions.push(ExtensionType::NonTransferable);
+    }
+
+    let space = match ExtensionType::try_calculate_account_len::<Mint>(&extensions) {
+        Ok(space) => space,
+        Err(_) => return err!(ProgramErrorCode::InvalidMintAccountSpace),
+    };
 
-    let meta_data_space = 250 + agreements.len() * 200;
+    // The token program appends the metadata to the mint, the update authority has a fixed size
+    let token_metadata = TokenMetadata {
+        mint: ctx.accounts.nft_mint.key(),
+        name: name.clone(),
+        symbol: symbol.clone(),
+        uri: uri.clone(),
+        additional_metadata: agreements
+            .iter()
+            .map(|agreement| (agreement.title.clone(), agreement.details.clone()))
+            .collect(),
+        ..Default::default()
+    };
+    let meta_data_space = token_metadata.tlv_size_of()?;
     let lamports_required = (Rent::get()?).minimum_balance(space + meta_data_space);
 
     // Create Mint account
@@ -139,6 +278,16 @@ pub fn initialize_mint_and_metadata(
         },
     );
 
+    // Let the config close the mint once the NFT is burned by close_service
+    invoke(
+        &spl_token_2022::instruction::initialize_mint_close_authority(
+            &spl_token_2022::id(),
+            ctx.accounts.nft_mint.key,
+            Some(&ctx.accounts.config_account.key()),
+        )?,
+        &[ctx.accounts.nft_mint.to_account_info()],
+    )?;
+
     if is_soulbound {
         // Initialize the NonTransferable extension
         // This instruction must come before the instruction to initialize the mint data
@@ -164,9 +313,9 @@ pub fn initialize_mint_and_metadata(
         ctx.accounts.config_account.to_account_info().key,
         ctx.accounts.nft_mint.key,
         ctx.accounts.config_account.to_account_info().key,
-        "Two Sided Services Marketplace".to_string(),
-        "TSSM".to_string(),
-        METADATA_URI.to_string(),
+        name,
+        symbol,
+        uri,
     );
 
     invoke_signed(
@@ -210,7 +359,8 @@ pub fn initialize_mint_and_metadata(
         signer
     ))?;
 
-    // Mint one token to the associated token account
+    // Mint one token to the associated token account. The config PDA keeps the mint authority so
+    // rent freed by later metadata changes can be withdrawn, the program never mints a second token
     token_2022::mint_to(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
@@ -224,18 +374,12 @@ pub fn initialize_mint_and_metadata(
         1,
     )?;
 
-    // Freeze the mint authority to make it an NFT
-    token_2022::set_authority(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            token_2022::SetAuthority {
-                current_authority: ctx.accounts.config_account.to_account_info(),
-                account_or_mint: ctx.accounts.nft_mint.to_account_info(),
-            },
-            signer,
-        ),
-        AuthorityType::MintTokens,
-        None,
+    // Refund anything above the rent of the final mint
+    withdraw_excess_mint_lamports(
+        ctx.accounts.nft_mint.to_account_info(),
+        ctx.accounts.vendor.to_account_info(),
+        ctx.accounts.config_account.to_account_info(),
+        signer,
     )?;
 
     Ok(())
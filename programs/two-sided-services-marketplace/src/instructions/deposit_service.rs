@@ -0,0 +1,77 @@
+use std::ops::DerefMut;
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+use crate::helper::transfer_nft_from_user_to_pool;
+use crate::{
+    error::ProgramErrorCode,
+    states::{ConfigAccount, ServiceAccount, CONFIG_SEED, SERVICE_SEED},
+};
+
+#[derive(Accounts)]
+pub struct DepositNFTService<'info> {
+    // Whoever holds the withdrawn NFT can deposit it and becomes the current vendor
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+    #[account(
+        seeds = [&CONFIG_SEED.as_bytes()],
+        bump = config_account.bump
+    )]
+    pub config_account: Account<'info, ConfigAccount>,
+    #[account(
+        mut,
+        associated_token::mint = nft_mint,
+        associated_token::authority = config_account,
+        associated_token::token_program = token_program
+    )]
+    pub config_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub nft_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = depositor,
+        token::token_program = token_program
+    )]
+    pub depositor_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [&SERVICE_SEED.as_bytes(), nft_mint.key().as_ref()],
+        bump = service_account.bump,
+        constraint = !service_account.is_in_custody @ProgramErrorCode::ServiceAlreadyInCustody
+    )]
+    pub service_account: Account<'info, ServiceAccount>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[event]
+pub struct DepositServiceEvent {
+    pub depositor: Pubkey,
+    pub previous_vendor: Pubkey,
+    pub nft_mint: Pubkey,
+}
+
+pub fn deposit_service(ctx: Context<DepositNFTService>) -> Result<()> {
+    transfer_nft_from_user_to_pool(
+        ctx.accounts.depositor_token_account.to_account_info(),
+        ctx.accounts.config_token_account.to_account_info(),
+        ctx.accounts.depositor.to_account_info(),
+        &ctx.accounts.nft_mint,
+        ctx.accounts.token_program.to_account_info(),
+    )?;
+
+    // The NFT may have changed hands while it was out of custody
+    let service_account = ctx.accounts.service_account.deref_mut();
+    let previous_vendor = service_account.current_vendor;
+    service_account.change_vendor(ctx.accounts.depositor.key());
+    service_account.is_in_custody = true;
+
+    emit!(DepositServiceEvent {
+        depositor: ctx.accounts.depositor.key(),
+        previous_vendor,
+        nft_mint: ctx.accounts.nft_mint.key(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use std::ops::DerefMut;
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, Token2022};
+
+use crate::{
+    error::ProgramErrorCode,
+    states::{ConfigAccount, ServiceAccount, CONFIG_SEED, SERVICE_SEED, UNIT_MINT_SEED},
+};
+
+#[derive(Accounts)]
+pub struct EnableInventory<'info> {
+    #[account(mut)]
+    pub vendor: Signer<'info>,
+    #[account(
+        seeds = [&CONFIG_SEED.as_bytes()],
+        bump = config_account.bump
+    )]
+    pub config_account: Account<'info, ConfigAccount>,
+    #[account(
+        mut,
+        seeds = [&SERVICE_SEED.as_bytes(), service_account.nft_mint.as_ref()],
+        bump = service_account.bump,
+        constraint = service_account.current_vendor == vendor.key() @ProgramErrorCode::InvalidCurrentVendor,
+        constraint = service_account.unit_mint.is_none() @ProgramErrorCode::InventoryAlreadyEnabled,
+        constraint = !service_account.is_in_auction @ProgramErrorCode::ServiceInAuction,
+        constraint = service_account.open_orders == 0 @ProgramErrorCode::ServiceHasOpenOrder
+    )]
+    pub service_account: Account<'info, ServiceAccount>,
+    // Units are minted by the config PDA, one per purchase
+    #[account(
+        init,
+        payer = vendor,
+        seeds = [&UNIT_MINT_SEED.as_bytes(), service_account.nft_mint.as_ref()],
+        bump,
+        mint::decimals = 0,
+        mint::authority = config_account,
+        mint::token_program = token_program
+    )]
+    pub unit_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub token_program: Program<'info, Token2022>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct EnableInventoryEvent {
+    pub vendor: Pubkey,
+    pub nft_mint: Pubkey,
+    pub unit_mint: Pubkey,
+    pub capacity: u32,
+}
+
+pub fn enable_inventory(ctx: Context<EnableInventory>, capacity: u32) -> Result<()> {
+    if capacity == 0 {
+        return Err(ProgramErrorCode::InvalidUnits.into());
+    }
+
+    let unit_mint = ctx.accounts.unit_mint.key();
+    let service_account = ctx.accounts.service_account.deref_mut();
+
+    service_account.unit_mint = Some(unit_mint);
+    service_account.capacity = capacity;
+    service_account.remaining_units = capacity;
+
+    emit!(EnableInventoryEvent {
+        vendor: ctx.accounts.vendor.key(),
+        nft_mint: service_account.nft_mint,
+        unit_mint,
+        capacity,
+    });
+
+    Ok(())
+}
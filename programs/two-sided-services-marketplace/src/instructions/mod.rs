@@ -20,4 +20,76 @@ pub mod update_ask_price;
 pub use update_ask_price::*;
 
 pub mod update_service_price;
-pub use update_service_price::*;
\ No newline at end of file
+pub use update_service_price::*;
+
+pub mod set_service_listed;
+pub use set_service_listed::*;
+
+pub mod cancel_ask;
+pub use cancel_ask::*;
+
+pub mod deposit_service;
+pub use deposit_service::*;
+
+pub mod purchase_order;
+pub use purchase_order::*;
+
+pub mod confirm_delivery;
+pub use confirm_delivery::*;
+
+pub mod refund_order;
+pub use refund_order::*;
+
+pub mod request_milestone_release;
+pub use request_milestone_release::*;
+
+pub mod approve_milestone_release;
+pub use approve_milestone_release::*;
+
+pub mod open_dispute;
+pub use open_dispute::*;
+
+pub mod resolve_dispute;
+pub use resolve_dispute::*;
+
+pub mod submit_review;
+pub use submit_review::*;
+
+pub mod update_vendor_profile;
+pub use update_vendor_profile::*;
+
+pub mod update_agreement;
+pub use update_agreement::*;
+
+pub mod remove_agreement;
+pub use remove_agreement::*;
+
+pub mod close_service;
+pub use close_service::*;
+
+pub mod set_service_expiry;
+pub use set_service_expiry::*;
+
+pub mod cancel_expired_ask;
+pub use cancel_expired_ask::*;
+
+pub mod start_auction;
+pub use start_auction::*;
+
+pub mod place_bid;
+pub use place_bid::*;
+
+pub mod settle_auction;
+pub use settle_auction::*;
+
+pub mod cancel_auction;
+pub use cancel_auction::*;
+
+pub mod set_dutch_auction;
+pub use set_dutch_auction::*;
+
+pub mod enable_inventory;
+pub use enable_inventory::*;
+
+pub mod restock_service;
+pub use restock_service::*;
\ No newline at end of file
@@ -0,0 +1,51 @@
+use std::ops::DerefMut;
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::ProgramErrorCode,
+    states::{OrderAccount, ORDER_SEED},
+};
+
+#[derive(Accounts)]
+pub struct OpenDispute<'info> {
+    // Either the buyer or the vendor of the order
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [&ORDER_SEED.as_bytes(), order_account.nft_mint.as_ref(), order_account.buyer.as_ref()],
+        bump = order_account.bump,
+        constraint = !order_account.is_disputed @ProgramErrorCode::OrderDisputed
+    )]
+    pub order_account: Account<'info, OrderAccount>,
+}
+
+#[event]
+pub struct OpenDisputeEvent {
+    pub authority: Pubkey,
+    pub buyer: Pubkey,
+    pub vendor: Pubkey,
+    pub nft_mint: Pubkey,
+    pub disputed_amount: u64,
+}
+
+pub fn open_dispute(ctx: Context<OpenDispute>) -> Result<()> {
+    let order_account = ctx.accounts.order_account.deref_mut();
+    let authority = ctx.accounts.authority.key();
+
+    if authority != order_account.buyer && authority != order_account.vendor {
+        return Err(ProgramErrorCode::Unauthorized.into());
+    }
+
+    order_account.is_disputed = true;
+
+    emit!(OpenDisputeEvent {
+        authority,
+        buyer: order_account.buyer,
+        vendor: order_account.vendor,
+        nft_mint: order_account.nft_mint,
+        disputed_amount: order_account.remaining_amount()?,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,171 @@
+use std::ops::DerefMut;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    constant::AUCTION_EXTENSION_WINDOW,
+    error::ProgramErrorCode,
+    helper::{has_expired, optional_account_info, token_payment, transfer_payment},
+    states::{AuctionAccount, ConfigAccount, ServiceAccount, AUCTION_SEED, SERVICE_SEED},
+};
+
+#[derive(Accounts)]
+pub struct PlaceBid<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    #[account(
+        constraint = !config_account.paused @ProgramErrorCode::MarketplacePaused
+    )]
+    pub config_account: Account<'info, ConfigAccount>,
+    #[account(
+        mut,
+        seeds = [&AUCTION_SEED.as_bytes(), auction_account.nft_mint.as_ref()],
+        bump = auction_account.bump,
+        constraint = auction_account.seller != bidder.key() @ProgramErrorCode::InvalidBidder,
+        constraint = auction_account.payment_mint == payment_mint.as_ref().map(|mint| mint.key()) @ProgramErrorCode::InvalidPaymentMint
+    )]
+    pub auction_account: Account<'info, AuctionAccount>,
+    // Bids stop once the listing is delisted, expired or turned into a multi-unit service
+    #[account(
+        seeds = [&SERVICE_SEED.as_bytes(), auction_account.nft_mint.as_ref()],
+        bump = service_account.bump,
+        constraint = service_account.is_listed @ProgramErrorCode::ServiceNotListed,
+        constraint = service_account.unit_mint.is_none() @ProgramErrorCode::InventoryAlreadyEnabled
+    )]
+    pub service_account: Account<'info, ServiceAccount>,
+    /// CHECK: The current highest bidder, refunded when outbid. Checked against the auction.
+    #[account(mut)]
+    pub previous_bidder: Option<UncheckedAccount<'info>>,
+
+    // The accounts below are only needed when the service is paid in an SPL token
+    pub payment_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = bidder,
+        token::token_program = token_program
+    )]
+    pub bidder_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    // Created for the outbid bidder if they closed it, so the refund cannot be blocked
+    #[account(
+        init_if_needed,
+        payer = bidder,
+        associated_token::mint = payment_mint,
+        associated_token::authority = previous_bidder,
+        associated_token::token_program = token_program
+    )]
+    pub previous_bidder_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = auction_account,
+        associated_token::token_program = token_program
+    )]
+    pub auction_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct PlaceBidEvent {
+    pub bidder: Pubkey,
+    pub nft_mint: Pubkey,
+    pub amount: u64,
+    pub previous_bidder: Option<Pubkey>,
+    pub end_time: i64,
+}
+
+pub fn place_bid(ctx: Context<PlaceBid>, amount: u64) -> Result<()> {
+    let auction_account = &ctx.accounts.auction_account;
+    let now = Clock::get()?.unix_timestamp;
+
+    if now >= auction_account.end_time {
+        return Err(ProgramErrorCode::AuctionEnded.into());
+    }
+
+    if has_expired(ctx.accounts.service_account.expires_at)? {
+        return Err(ProgramErrorCode::ListingExpired.into());
+    }
+
+    // The first bid has to meet the reserve, the next ones have to beat the highest bid
+    let minimum_bid = match auction_account.highest_bidder {
+        Some(_) => auction_account
+            .highest_bid
+            .checked_add(auction_account.min_bid_increment.max(1))
+            .ok_or(ProgramErrorCode::Overflow)?,
+        None => auction_account.reserve_price,
+    };
+    if amount < minimum_bid {
+        return Err(ProgramErrorCode::BidTooLow.into());
+    }
+
+    let token_payment = token_payment(&ctx.accounts.payment_mint, &ctx.accounts.token_program)?;
+    let auction_token_account = optional_account_info(&ctx.accounts.auction_token_account);
+
+    // Refund the bidder who has just been outbid
+    let previous_bidder = auction_account.highest_bidder;
+    if let Some(previous_bidder) = previous_bidder {
+        let previous_bidder_account = ctx
+            .accounts
+            .previous_bidder
+            .as_ref()
+            .filter(|account| account.key() == previous_bidder)
+            .ok_or(ProgramErrorCode::InvalidBidder)?;
+
+        let nft_mint = auction_account.nft_mint;
+        let signer: &[&[&[u8]]] = &[&[
+            AUCTION_SEED.as_bytes(),
+            nft_mint.as_ref(),
+            &[auction_account.bump],
+        ]];
+
+        transfer_payment(
+            auction_account.to_account_info(),
+            auction_token_account.clone(),
+            previous_bidder_account.to_account_info(),
+            optional_account_info(&ctx.accounts.previous_bidder_token_account),
+            token_payment.as_ref(),
+            auction_account.highest_bid,
+            Some(signer),
+        )?;
+    }
+
+    // Escrow the new bid until it is outbid or the auction is settled
+    transfer_payment(
+        ctx.accounts.bidder.to_account_info(),
+        optional_account_info(&ctx.accounts.bidder_token_account),
+        auction_account.to_account_info(),
+        auction_token_account,
+        token_payment.as_ref(),
+        amount,
+        None,
+    )?;
+
+    let auction_account = ctx.accounts.auction_account.deref_mut();
+    auction_account.highest_bidder = Some(ctx.accounts.bidder.key());
+    auction_account.highest_bid = amount;
+
+    // A late bid gives the other bidders time to answer
+    let extended_end_time = now
+        .checked_add(AUCTION_EXTENSION_WINDOW)
+        .ok_or(ProgramErrorCode::Overflow)?;
+    if auction_account.end_time < extended_end_time {
+        auction_account.end_time = extended_end_time;
+    }
+
+    emit!(PlaceBidEvent {
+        bidder: ctx.accounts.bidder.key(),
+        nft_mint: auction_account.nft_mint,
+        amount,
+        previous_bidder,
+        end_time: auction_account.end_time,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,140 @@
+use std::ops::DerefMut;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    error::ProgramErrorCode,
+    helper::{has_expired, optional_account_info, token_payment, transfer_payment},
+    states::{ConfigAccount, OrderAccount, ServiceAccount, ORDER_SEED, SERVICE_SEED},
+};
+
+#[derive(Accounts)]
+pub struct PurchaseOrder<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    #[account(
+        constraint = !config_account.paused @ProgramErrorCode::MarketplacePaused
+    )]
+    pub config_account: Account<'info, ConfigAccount>,
+    #[account(
+        mut,
+        seeds = [&SERVICE_SEED.as_bytes(), service_account.nft_mint.as_ref()],
+        bump = service_account.bump,
+        constraint = service_account.is_listed @ProgramErrorCode::ServiceNotListed,
+        constraint = service_account.is_in_custody @ProgramErrorCode::ServiceNotInCustody,
+        constraint = service_account.current_vendor != buyer.key() @ProgramErrorCode::CannotBuyOwnService,
+        constraint = service_account.open_orders == 0 @ProgramErrorCode::ServiceHasOpenOrder,
+        constraint = !service_account.is_in_auction @ProgramErrorCode::ServiceInAuction,
+        // The milestones add up to the listed price, not to a declining one
+        constraint = service_account.dutch_auction.is_none() @ProgramErrorCode::ServiceInDutchAuction,
+        // Completing an order hands over the whole listing, units are sold with buy_service
+        constraint = service_account.unit_mint.is_none() @ProgramErrorCode::InventoryAlreadyEnabled,
+        constraint = service_account.payment_mint == payment_mint.as_ref().map(|mint| mint.key()) @ProgramErrorCode::InvalidPaymentMint
+    )]
+    pub service_account: Account<'info, ServiceAccount>,
+    #[account(
+        init,
+        payer = buyer,
+        space = OrderAccount::INIT_SPACE,
+        seeds = [&ORDER_SEED.as_bytes(), service_account.nft_mint.as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub order_account: Account<'info, OrderAccount>,
+
+    // The accounts below are only needed when the service is paid in an SPL token
+    pub payment_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = buyer,
+        token::token_program = token_program
+    )]
+    pub buyer_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        associated_token::mint = payment_mint,
+        associated_token::authority = order_account,
+        associated_token::token_program = token_program
+    )]
+    pub order_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct PurchaseOrderEvent {
+    pub buyer: Pubkey,
+    pub vendor: Pubkey,
+    pub nft_mint: Pubkey,
+    pub payment_mint: Option<Pubkey>,
+    pub amount: u64,
+    pub refundable_at: i64,
+}
+
+pub fn purchase_order(ctx: Context<PurchaseOrder>, max_price: u64) -> Result<()> {
+    let service_account = &ctx.accounts.service_account;
+    if has_expired(service_account.expires_at)? {
+        return Err(ProgramErrorCode::ListingExpired.into());
+    }
+
+    // The vendor may have raised the price with update_service_price since the buyer looked at it
+    if service_account.price > max_price {
+        return Err(ProgramErrorCode::PriceAboveMaximum.into());
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    let refundable_at = now
+        .checked_add(ctx.accounts.config_account.order_refund_timeout)
+        .ok_or(ProgramErrorCode::Overflow)?;
+
+    let order_account = ctx.accounts.order_account.deref_mut();
+    order_account.bump = ctx.bumps.order_account;
+    order_account.buyer = ctx.accounts.buyer.key();
+    order_account.vendor = service_account.current_vendor;
+    order_account.nft_mint = service_account.nft_mint;
+    order_account.payment_mint = service_account.payment_mint;
+    order_account.amount = service_account.price;
+    order_account.created_at = now;
+    order_account.refundable_at = refundable_at;
+    order_account.milestone_amounts = service_account.milestone_amounts.clone();
+    order_account.released_milestones = 0;
+    order_account.released_amount = 0;
+    order_account.release_requested = false;
+    order_account.is_disputed = false;
+
+    // Escrow the payment until the buyer confirms delivery
+    transfer_payment(
+        ctx.accounts.buyer.to_account_info(),
+        optional_account_info(&ctx.accounts.buyer_token_account),
+        ctx.accounts.order_account.to_account_info(),
+        optional_account_info(&ctx.accounts.order_token_account),
+        token_payment(&ctx.accounts.payment_mint, &ctx.accounts.token_program)?.as_ref(),
+        service_account.price,
+        None,
+    )?;
+
+    let order_account = &ctx.accounts.order_account;
+    emit!(PurchaseOrderEvent {
+        buyer: order_account.buyer,
+        vendor: order_account.vendor,
+        nft_mint: order_account.nft_mint,
+        payment_mint: order_account.payment_mint,
+        amount: order_account.amount,
+        refundable_at,
+    });
+
+    let service_account = ctx.accounts.service_account.deref_mut();
+    service_account.open_orders = service_account
+        .open_orders
+        .checked_add(1)
+        .ok_or(ProgramErrorCode::Overflow)?;
+
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use std::ops::DerefMut;
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    error::ProgramErrorCode,
+    helper::{close_token_account, optional_account_info, token_payment, transfer_payment},
+    states::{OrderAccount, ServiceAccount, ORDER_SEED, SERVICE_SEED},
+};
+
+#[derive(Accounts)]
+pub struct RefundOrder<'info> {
+    // The vendor can refund at any time, the buyer only after the refund timeout
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: This account is the buyer of the order and receives the refund.
+    #[account(mut)]
+    pub buyer: AccountInfo<'info>,
+    #[account(
+        mut,
+        close = buyer,
+        seeds = [&ORDER_SEED.as_bytes(), order_account.nft_mint.as_ref(), buyer.key().as_ref()],
+        bump = order_account.bump,
+        constraint = order_account.buyer == buyer.key() @ProgramErrorCode::InvalidBuyer,
+        constraint = order_account.payment_mint == payment_mint.as_ref().map(|mint| mint.key()) @ProgramErrorCode::InvalidPaymentMint
+    )]
+    pub order_account: Account<'info, OrderAccount>,
+    #[account(
+        mut,
+        seeds = [&SERVICE_SEED.as_bytes(), order_account.nft_mint.as_ref()],
+        bump = service_account.bump
+    )]
+    pub service_account: Account<'info, ServiceAccount>,
+
+    // The accounts below are only needed when the order is paid in an SPL token
+    pub payment_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = order_account,
+        associated_token::token_program = token_program
+    )]
+    pub order_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = buyer,
+        token::token_program = token_program
+    )]
+    pub buyer_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct RefundOrderEvent {
+    pub authority: Pubkey,
+    pub buyer: Pubkey,
+    pub vendor: Pubkey,
+    pub nft_mint: Pubkey,
+    pub refund_amount: u64,
+}
+
+pub fn refund_order(ctx: Context<RefundOrder>) -> Result<()> {
+    let order_account = &ctx.accounts.order_account;
+    let authority = ctx.accounts.authority.key();
+
+    if authority == order_account.buyer {
+        // A disputed order is settled by an arbiter, not by the timeout
+        if order_account.is_disputed {
+            return Err(ProgramErrorCode::OrderDisputed.into());
+        }
+        if Clock::get()?.unix_timestamp < order_account.refundable_at {
+            return Err(ProgramErrorCode::OrderNotRefundable.into());
+        }
+    } else if authority != order_account.vendor {
+        return Err(ProgramErrorCode::Unauthorized.into());
+    }
+
+    // Milestones already released stay with the vendor
+    let refund_amount = order_account.remaining_amount()?;
+    let nft_mint = order_account.nft_mint;
+    let buyer = order_account.buyer;
+    let signer: &[&[&[u8]]] = &[&[
+        ORDER_SEED.as_bytes(),
+        nft_mint.as_ref(),
+        buyer.as_ref(),
+        &[order_account.bump],
+    ]];
+
+    // Lamport escrow is refunded when the order account is closed, token escrow is refunded here
+    if let Some(token_payment) =
+        token_payment(&ctx.accounts.payment_mint, &ctx.accounts.token_program)?
+    {
+        transfer_payment(
+            order_account.to_account_info(),
+            optional_account_info(&ctx.accounts.order_token_account),
+            ctx.accounts.buyer.to_account_info(),
+            optional_account_info(&ctx.accounts.buyer_token_account),
+            Some(&token_payment),
+            refund_amount,
+            Some(signer),
+        )?;
+
+        close_token_account(
+            optional_account_info(&ctx.accounts.order_token_account)
+                .ok_or(ProgramErrorCode::MissingTokenAccount)?,
+            ctx.accounts.buyer.to_account_info(),
+            order_account.to_account_info(),
+            token_payment.token_program.to_account_info(),
+            signer,
+        )?;
+    }
+
+    emit!(RefundOrderEvent {
+        authority,
+        buyer,
+        vendor: order_account.vendor,
+        nft_mint,
+        refund_amount,
+    });
+
+    let service_account = ctx.accounts.service_account.deref_mut();
+    service_account.open_orders = service_account
+        .open_orders
+        .checked_sub(1)
+        .ok_or(ProgramErrorCode::Overflow)?;
+
+    Ok(())
+}
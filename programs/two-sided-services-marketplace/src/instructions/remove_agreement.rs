@@ -0,0 +1,105 @@
+use std::ops::DerefMut;
+
+use anchor_lang::{prelude::*, solana_program::program::invoke_signed};
+use anchor_spl::token_interface::{spl_token_2022, spl_token_metadata_interface, Token2022};
+
+use crate::{
+    error::ProgramErrorCode,
+    helper::{read_token_metadata, withdraw_excess_mint_lamports},
+    states::{ConfigAccount, ServiceAccount, CONFIG_SEED, SERVICE_SEED},
+};
+
+#[derive(Accounts)]
+pub struct RemoveAgreement<'info> {
+    #[account(mut)]
+    pub vendor: Signer<'info>,
+    #[account(
+        seeds = [&CONFIG_SEED.as_bytes()],
+        bump = config_account.bump
+    )]
+    pub config_account: Account<'info, ConfigAccount>,
+    #[account(
+        mut,
+        seeds = [&SERVICE_SEED.as_bytes(), service_account.nft_mint.as_ref()],
+        bump = service_account.bump,
+        constraint = service_account.current_vendor == vendor.key() @ProgramErrorCode::InvalidCurrentVendor,
+        constraint = service_account.is_in_custody @ProgramErrorCode::ServiceNotInCustody,
+        constraint = !service_account.is_in_auction @ProgramErrorCode::ServiceInAuction,
+        constraint = service_account.open_orders == 0 @ProgramErrorCode::ServiceHasOpenOrder
+    )]
+    pub service_account: Account<'info, ServiceAccount>,
+    /// CHECK: The service NFT mint, its metadata is updated by the token program.
+    #[account(
+        mut,
+        address = service_account.nft_mint @ProgramErrorCode::InvalidNftMint
+    )]
+    pub nft_mint: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[event]
+pub struct RemoveAgreementEvent {
+    pub vendor: Pubkey,
+    pub nft_mint: Pubkey,
+    pub title: String,
+}
+
+pub fn remove_agreement(ctx: Context<RemoveAgreement>, title: String) -> Result<()> {
+    let nft_mint = ctx.accounts.nft_mint.to_account_info();
+
+    // Agreements are stored in creation order, the same order as the milestones
+    let token_metadata = read_token_metadata(&nft_mint)?;
+    let index = token_metadata
+        .additional_metadata
+        .iter()
+        .position(|(key, _)| *key == title)
+        .ok_or(ProgramErrorCode::AgreementNotFound)?;
+
+    // Agreements added after creation have no milestone. A removed milestone is paid with the
+    // last one so the price stays the same, at least one milestone has to remain to carry it
+    let service_account = ctx.accounts.service_account.deref_mut();
+    if index < service_account.milestone_amounts.len() {
+        if service_account.milestone_amounts.len() == 1 {
+            return Err(ProgramErrorCode::LastMilestoneAgreement.into());
+        }
+
+        let removed_amount = service_account.milestone_amounts.remove(index);
+        if let Some(last) = service_account.milestone_amounts.last_mut() {
+            *last = last
+                .checked_add(removed_amount)
+                .ok_or(ProgramErrorCode::Overflow)?;
+        }
+    }
+
+    // The config PDA is the metadata update authority
+    let signer: &[&[&[u8]]] = &[&[CONFIG_SEED.as_bytes(), &[ctx.accounts.config_account.bump]]];
+
+    invoke_signed(
+        &spl_token_metadata_interface::instruction::remove_key(
+            &spl_token_2022::id(),
+            nft_mint.key,
+            &ctx.accounts.config_account.key(),
+            title.clone(),
+            false,
+        ),
+        &[nft_mint.clone(), ctx.accounts.config_account.to_account_info()],
+        signer,
+    )?;
+
+    // Token-2022 shrinks the mint, the rent it no longer needs goes back to the vendor
+    withdraw_excess_mint_lamports(
+        nft_mint.clone(),
+        ctx.accounts.vendor.to_account_info(),
+        ctx.accounts.config_account.to_account_info(),
+        signer,
+    )?;
+
+    emit!(RemoveAgreementEvent {
+        vendor: ctx.accounts.vendor.key(),
+        nft_mint: nft_mint.key(),
+        title,
+    });
+
+    Ok(())
+}
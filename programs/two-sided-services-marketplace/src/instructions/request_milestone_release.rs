@@ -0,0 +1,50 @@
+use std::ops::DerefMut;
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::ProgramErrorCode,
+    states::{OrderAccount, ORDER_SEED},
+};
+
+#[derive(Accounts)]
+pub struct RequestMilestoneRelease<'info> {
+    pub vendor: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [&ORDER_SEED.as_bytes(), order_account.nft_mint.as_ref(), order_account.buyer.as_ref()],
+        bump = order_account.bump,
+        constraint = order_account.vendor == vendor.key() @ProgramErrorCode::InvalidCurrentVendor,
+        constraint = !order_account.is_disputed @ProgramErrorCode::OrderDisputed
+    )]
+    pub order_account: Account<'info, OrderAccount>,
+}
+
+#[event]
+pub struct RequestMilestoneReleaseEvent {
+    pub vendor: Pubkey,
+    pub buyer: Pubkey,
+    pub nft_mint: Pubkey,
+    pub milestone_index: u8,
+    pub amount: u64,
+}
+
+pub fn request_milestone_release(ctx: Context<RequestMilestoneRelease>) -> Result<()> {
+    let order_account = ctx.accounts.order_account.deref_mut();
+
+    let amount = order_account
+        .next_milestone_amount()
+        .ok_or(ProgramErrorCode::NoMilestoneToRelease)?;
+
+    order_account.release_requested = true;
+
+    emit!(RequestMilestoneReleaseEvent {
+        vendor: ctx.accounts.vendor.key(),
+        buyer: order_account.buyer,
+        nft_mint: order_account.nft_mint,
+        milestone_index: order_account.released_milestones,
+        amount,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,208 @@
+use std::ops::DerefMut;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    constant::MAX_FEE_BASIS_POINTS,
+    error::ProgramErrorCode,
+    helper::{
+        calculate_fee, close_token_account, optional_account_info, settle_sale, token_payment,
+        transfer_payment, SaleAmounts, SalePayment,
+    },
+    states::{
+        ConfigAccount, OrderAccount, ServiceAccount, TreasuryAccount, CONFIG_SEED, ORDER_SEED,
+        SERVICE_SEED, TREASURY_SEED,
+    },
+};
+
+#[derive(Accounts)]
+pub struct ResolveDispute<'info> {
+    #[account(mut)]
+    pub arbiter: Signer<'info>,
+    #[account(
+        seeds = [&CONFIG_SEED.as_bytes()],
+        bump = config_account.bump,
+        constraint = config_account.arbiters.contains(&arbiter.key()) @ProgramErrorCode::Unauthorized
+    )]
+    pub config_account: Account<'info, ConfigAccount>,
+    /// CHECK: This account is the buyer of the order and is used to send lamports.
+    #[account(mut)]
+    pub buyer: AccountInfo<'info>,
+    /// CHECK: This account is the vendor of the order and is used to send lamports.
+    #[account(
+        mut,
+        constraint = vendor.key() == order_account.vendor @ProgramErrorCode::InvalidCurrentVendor
+    )]
+    pub vendor: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [&TREASURY_SEED.as_bytes()],
+        bump = treasury_account.bump
+    )]
+    pub treasury_account: Account<'info, TreasuryAccount>,
+    #[account(
+        mut,
+        close = buyer,
+        seeds = [&ORDER_SEED.as_bytes(), order_account.nft_mint.as_ref(), buyer.key().as_ref()],
+        bump = order_account.bump,
+        constraint = order_account.buyer == buyer.key() @ProgramErrorCode::InvalidBuyer,
+        constraint = order_account.is_disputed @ProgramErrorCode::OrderNotDisputed,
+        constraint = order_account.payment_mint == payment_mint.as_ref().map(|mint| mint.key()) @ProgramErrorCode::InvalidPaymentMint
+    )]
+    pub order_account: Account<'info, OrderAccount>,
+    #[account(
+        mut,
+        seeds = [&SERVICE_SEED.as_bytes(), order_account.nft_mint.as_ref()],
+        bump = service_account.bump
+    )]
+    pub service_account: Account<'info, ServiceAccount>,
+
+    // The accounts below are only needed when the order is paid in an SPL token
+    pub payment_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = order_account,
+        associated_token::token_program = token_program
+    )]
+    pub order_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = buyer,
+        token::token_program = token_program
+    )]
+    pub buyer_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = vendor,
+        token::token_program = token_program
+    )]
+    pub vendor_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        init_if_needed,
+        payer = arbiter,
+        associated_token::mint = payment_mint,
+        associated_token::authority = treasury_account,
+        associated_token::token_program = token_program
+    )]
+    pub treasury_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct ResolveDisputeEvent {
+    pub arbiter: Pubkey,
+    pub buyer: Pubkey,
+    pub vendor: Pubkey,
+    pub nft_mint: Pubkey,
+    pub buyer_amount: u64,
+    pub vendor_amount: u64,
+    pub royalty_amount: u64,
+    pub protocol_fee_amount: u64,
+    pub remaining_amount: u64,
+}
+
+// Splits the escrow that is still held by the order, the service does not change hands.
+// Royalty recipients for the vendor share are passed as remaining accounts, see `pay_royalties`
+pub fn resolve_dispute<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, ResolveDispute<'info>>,
+    buyer_share_basis_points: u16,
+) -> Result<()> {
+    if buyer_share_basis_points > MAX_FEE_BASIS_POINTS {
+        return Err(ProgramErrorCode::InvalidShareBasisPoints.into());
+    }
+
+    let order_account = &ctx.accounts.order_account;
+    let token_payment = token_payment(&ctx.accounts.payment_mint, &ctx.accounts.token_program)?;
+    let order_token_account = optional_account_info(&ctx.accounts.order_token_account);
+
+    let disputed_amount = order_account.remaining_amount()?;
+    let buyer_amount = calculate_fee(disputed_amount, buyer_share_basis_points)?;
+    let vendor_amount = disputed_amount
+        .checked_sub(buyer_amount)
+        .ok_or(ProgramErrorCode::Overflow)?;
+
+    let nft_mint = order_account.nft_mint;
+    let buyer = order_account.buyer;
+    let signer: &[&[&[u8]]] = &[&[
+        ORDER_SEED.as_bytes(),
+        nft_mint.as_ref(),
+        buyer.as_ref(),
+        &[order_account.bump],
+    ]];
+
+    let service_account = &ctx.accounts.service_account;
+
+    // The vendor share is paid like any other settlement, fees and royalties included
+    let SaleAmounts {
+        royalty_amount,
+        protocol_fee_amount,
+        remaining_amount,
+    } = settle_sale(
+        &SalePayment {
+            from: order_account.to_account_info(),
+            from_token_account: order_token_account.clone(),
+            vendor: ctx.accounts.vendor.to_account_info(),
+            vendor_token_account: optional_account_info(&ctx.accounts.vendor_token_account),
+            treasury: ctx.accounts.treasury_account.to_account_info(),
+            treasury_token_account: optional_account_info(&ctx.accounts.treasury_token_account),
+            token_payment,
+            pda_signer_seeds: Some(signer),
+        },
+        vendor_amount,
+        ctx.accounts.config_account.protocol_fee_basis_points,
+        service_account.resale_royalty_basis_points(),
+        &service_account.creators,
+        ctx.remaining_accounts,
+    )?;
+
+    // Lamports left for the buyer are returned when the order account is closed
+    if let Some(token_payment) = &token_payment {
+        transfer_payment(
+            order_account.to_account_info(),
+            order_token_account.clone(),
+            ctx.accounts.buyer.to_account_info(),
+            optional_account_info(&ctx.accounts.buyer_token_account),
+            Some(token_payment),
+            buyer_amount,
+            Some(signer),
+        )?;
+
+        close_token_account(
+            order_token_account.ok_or(ProgramErrorCode::MissingTokenAccount)?,
+            ctx.accounts.buyer.to_account_info(),
+            order_account.to_account_info(),
+            token_payment.token_program.to_account_info(),
+            signer,
+        )?;
+    }
+
+    let service_account = ctx.accounts.service_account.deref_mut();
+    service_account.open_orders = service_account
+        .open_orders
+        .checked_sub(1)
+        .ok_or(ProgramErrorCode::Overflow)?;
+
+    emit!(ResolveDisputeEvent {
+        arbiter: ctx.accounts.arbiter.key(),
+        buyer,
+        vendor: ctx.accounts.vendor.key(),
+        nft_mint,
+        buyer_amount,
+        vendor_amount,
+        royalty_amount,
+        protocol_fee_amount,
+        remaining_amount,
+    });
+
+    Ok(())
+}
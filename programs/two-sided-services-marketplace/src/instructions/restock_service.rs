@@ -0,0 +1,53 @@
+use std::ops::DerefMut;
+
+use anchor_lang::prelude::*;
+
+use crate::{error::ProgramErrorCode, ServiceAccount};
+
+#[derive(Accounts)]
+pub struct RestockService<'info> {
+    #[account(mut)]
+    pub vendor: Signer<'info>,
+    #[account(
+        mut,
+        constraint = service_account.current_vendor == vendor.key() @ProgramErrorCode::Unauthorized,
+        constraint = service_account.unit_mint.is_some() @ProgramErrorCode::InventoryNotEnabled
+    )]
+    pub service_account: Account<'info, ServiceAccount>,
+}
+
+#[event]
+pub struct RestockServiceEvent {
+    pub vendor: Pubkey,
+    pub nft_mint: Pubkey,
+    pub units: u32,
+    pub capacity: u32,
+    pub remaining_units: u32,
+}
+
+pub fn restock_service(ctx: Context<RestockService>, units: u32) -> Result<()> {
+    if units == 0 {
+        return Err(ProgramErrorCode::InvalidUnits.into());
+    }
+
+    let service_account = ctx.accounts.service_account.deref_mut();
+
+    service_account.capacity = service_account
+        .capacity
+        .checked_add(units)
+        .ok_or(ProgramErrorCode::Overflow)?;
+    service_account.remaining_units = service_account
+        .remaining_units
+        .checked_add(units)
+        .ok_or(ProgramErrorCode::Overflow)?;
+
+    emit!(RestockServiceEvent {
+        vendor: ctx.accounts.vendor.key(),
+        nft_mint: service_account.nft_mint,
+        units,
+        capacity: service_account.capacity,
+        remaining_units: service_account.remaining_units,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use std::ops::DerefMut;
+
+use anchor_lang::prelude::*;
+
+use crate::{error::ProgramErrorCode, states::DutchAuction, ServiceAccount};
+
+#[derive(Accounts)]
+pub struct SetDutchAuction<'info> {
+    #[account(mut)]
+    pub vendor: Signer<'info>,
+    #[account(
+        mut,
+        constraint = service_account.current_vendor == vendor.key() @ProgramErrorCode::Unauthorized
+    )]
+    pub service_account: Account<'info, ServiceAccount>,
+}
+
+#[event]
+pub struct SetDutchAuctionEvent {
+    pub vendor: Pubkey,
+    pub nft_mint: Pubkey,
+    pub dutch_auction: Option<DutchAuction>,
+}
+
+// Pass None to go back to the fixed price
+pub fn set_dutch_auction(
+    ctx: Context<SetDutchAuction>,
+    dutch_auction: Option<DutchAuction>,
+) -> Result<()> {
+    if let Some(dutch_auction) = &dutch_auction {
+        dutch_auction.validate()?;
+    }
+
+    let service_account = ctx.accounts.service_account.deref_mut();
+
+    service_account.dutch_auction = dutch_auction.clone();
+
+    emit!(SetDutchAuctionEvent {
+        vendor: ctx.accounts.vendor.key(),
+        nft_mint: service_account.nft_mint,
+        dutch_auction,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use std::ops::DerefMut;
+
+use anchor_lang::prelude::*;
+
+use crate::{error::ProgramErrorCode, helper::validate_expiry, ServiceAccount};
+
+#[derive(Accounts)]
+pub struct SetServiceExpiry<'info> {
+    #[account(mut)]
+    pub vendor: Signer<'info>,
+    #[account(
+        mut,
+        constraint = service_account.current_vendor == vendor.key() @ProgramErrorCode::Unauthorized
+    )]
+    pub service_account: Account<'info, ServiceAccount>,
+}
+
+#[event]
+pub struct SetServiceExpiryEvent {
+    pub vendor: Pubkey,
+    pub nft_mint: Pubkey,
+    pub expires_at: Option<i64>,
+}
+
+pub fn set_service_expiry(ctx: Context<SetServiceExpiry>, expires_at: Option<i64>) -> Result<()> {
+    validate_expiry(expires_at)?;
+
+    let service_account = ctx.accounts.service_account.deref_mut();
+
+    service_account.expires_at = expires_at;
+
+    emit!(SetServiceExpiryEvent {
+        vendor: ctx.accounts.vendor.key(),
+        nft_mint: service_account.nft_mint,
+        expires_at,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use std::ops::DerefMut;
+
+use anchor_lang::prelude::*;
+
+use crate::{error::ProgramErrorCode, ServiceAccount};
+
+#[derive(Accounts)]
+pub struct SetServiceListed<'info> {
+    #[account(mut)]
+    pub vendor: Signer<'info>,
+    #[account(
+        mut,
+        constraint = service_account.current_vendor == vendor.key() @ProgramErrorCode::Unauthorized
+    )]
+    pub service_account: Account<'info, ServiceAccount>,
+}
+
+#[event]
+pub struct SetServiceListedEvent {
+    pub vendor: Pubkey,
+    pub nft_mint: Pubkey,
+    pub is_listed: bool,
+}
+
+pub fn set_service_listed(ctx: Context<SetServiceListed>, is_listed: bool) -> Result<()> {
+    let service_account = ctx.accounts.service_account.deref_mut();
+
+    service_account.is_listed = is_listed;
+
+    emit!(SetServiceListedEvent {
+        vendor: ctx.accounts.vendor.key(),
+        nft_mint: service_account.nft_mint,
+        is_listed,
+    });
+
+    Ok(())
+}
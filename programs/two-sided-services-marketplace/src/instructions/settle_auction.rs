@@ -0,0 +1,225 @@
+use std::ops::DerefMut;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    error::ProgramErrorCode,
+    helper::{
+        close_token_account, optional_account_info, settle_sale, token_payment, SaleAmounts,
+        SalePayment,
+    },
+    states::{
+        AuctionAccount, ConfigAccount, PurchaseReceiptAccount, ServiceAccount, TreasuryAccount,
+        VendorProfileAccount, AUCTION_SEED, CONFIG_SEED, RECEIPT_SEED, SERVICE_SEED,
+        TREASURY_SEED, VENDOR_PROFILE_SEED,
+    },
+};
+
+#[derive(Accounts)]
+pub struct SettleAuction<'info> {
+    // Anyone can settle an auction once it has ended
+    #[account(mut)]
+    pub settler: Signer<'info>,
+    /// CHECK: This account is the seller and is used to send lamports.
+    #[account(
+        mut,
+        address = auction_account.seller @ProgramErrorCode::InvalidCurrentVendor
+    )]
+    pub seller: AccountInfo<'info>,
+    #[account(
+        seeds = [&CONFIG_SEED.as_bytes()],
+        bump = config_account.bump
+    )]
+    pub config_account: Account<'info, ConfigAccount>,
+    #[account(
+        mut,
+        seeds = [&TREASURY_SEED.as_bytes()],
+        bump = treasury_account.bump
+    )]
+    pub treasury_account: Account<'info, TreasuryAccount>,
+    #[account(
+        mut,
+        seeds = [&SERVICE_SEED.as_bytes(), auction_account.nft_mint.as_ref()],
+        bump = service_account.bump
+    )]
+    pub service_account: Account<'info, ServiceAccount>,
+    // The auction rent goes back to the seller who paid it
+    #[account(
+        mut,
+        close = seller,
+        seeds = [&AUCTION_SEED.as_bytes(), auction_account.nft_mint.as_ref()],
+        bump = auction_account.bump,
+        constraint = auction_account.payment_mint == payment_mint.as_ref().map(|mint| mint.key()) @ProgramErrorCode::InvalidPaymentMint
+    )]
+    pub auction_account: Account<'info, AuctionAccount>,
+    // Proves the purchase when the winner submits a review, only needed when there is a winner
+    #[account(
+        init_if_needed,
+        payer = settler,
+        space = PurchaseReceiptAccount::INIT_SPACE,
+        seeds = [&RECEIPT_SEED.as_bytes(), auction_account.nft_mint.as_ref(), auction_account.highest_bidder.unwrap_or_default().as_ref()],
+        bump,
+        constraint = auction_account.highest_bidder.is_some() @ProgramErrorCode::InvalidBidder
+    )]
+    pub receipt_account: Option<Box<Account<'info, PurchaseReceiptAccount>>>,
+    #[account(
+        init_if_needed,
+        payer = settler,
+        space = VendorProfileAccount::INIT_SPACE,
+        seeds = [&VENDOR_PROFILE_SEED.as_bytes(), seller.key().as_ref()],
+        bump
+    )]
+    pub vendor_profile: Box<Account<'info, VendorProfileAccount>>,
+
+    // The accounts below are only needed when the service is paid in an SPL token
+    pub payment_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = auction_account,
+        associated_token::token_program = token_program
+    )]
+    pub auction_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = seller,
+        token::token_program = token_program
+    )]
+    pub seller_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        init_if_needed,
+        payer = settler,
+        associated_token::mint = payment_mint,
+        associated_token::authority = treasury_account,
+        associated_token::token_program = token_program
+    )]
+    pub treasury_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+
+    pub rent: Sysvar<'info, Rent>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct SettleAuctionEvent {
+    pub seller: Pubkey,
+    pub winner: Option<Pubkey>,
+    pub nft_mint: Pubkey,
+    pub payment_mint: Option<Pubkey>,
+    pub price: u64,
+    pub royalty_amount: u64,
+    pub protocol_fee_amount: u64,
+    pub remaining_amount: u64,
+}
+
+// Royalty recipients are passed as remaining accounts, see `pay_royalties`
+pub fn settle_auction<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, SettleAuction<'info>>,
+) -> Result<()> {
+    let accounts = &ctx.accounts;
+    let auction_account = &accounts.auction_account;
+    let service_account = &accounts.service_account;
+
+    if Clock::get()?.unix_timestamp < auction_account.end_time {
+        return Err(ProgramErrorCode::AuctionNotEnded.into());
+    }
+
+    let token_payment = token_payment(&accounts.payment_mint, &accounts.token_program)?;
+    let token_program = token_payment
+        .as_ref()
+        .map(|token_payment| token_payment.token_program.to_account_info());
+    let auction_token_account = optional_account_info(&accounts.auction_token_account);
+
+    let nft_mint = auction_account.nft_mint;
+    let winner = auction_account.highest_bidder;
+    let price = auction_account.highest_bid;
+    let signer: &[&[&[u8]]] = &[&[
+        AUCTION_SEED.as_bytes(),
+        nft_mint.as_ref(),
+        &[auction_account.bump],
+    ]];
+
+    // Without a bid the service simply stays with the seller
+    let sale_amounts = match winner {
+        Some(_) => settle_sale(
+            &SalePayment {
+                from: auction_account.to_account_info(),
+                from_token_account: auction_token_account.clone(),
+                vendor: accounts.seller.to_account_info(),
+                vendor_token_account: optional_account_info(&accounts.seller_token_account),
+                treasury: accounts.treasury_account.to_account_info(),
+                treasury_token_account: optional_account_info(&accounts.treasury_token_account),
+                token_payment,
+                pda_signer_seeds: Some(signer),
+            },
+            price,
+            accounts.config_account.protocol_fee_basis_points,
+            service_account.resale_royalty_basis_points(),
+            &service_account.creators,
+            ctx.remaining_accounts,
+        )?,
+        None => SaleAmounts::default(),
+    };
+
+    // The emptied token escrow is closed along with the auction account
+    if let (Some(token_program), Some(auction_token_account)) =
+        (token_program, auction_token_account)
+    {
+        close_token_account(
+            auction_token_account,
+            accounts.seller.to_account_info(),
+            auction_account.to_account_info(),
+            token_program,
+            signer,
+        )?;
+    }
+
+    let payment_mint = auction_account.payment_mint;
+
+    let vendor_profile = ctx.accounts.vendor_profile.deref_mut();
+    vendor_profile.init_if_needed(ctx.bumps.vendor_profile, ctx.accounts.seller.key());
+
+    // The winner gets a receipt and the seller a sale, as with buy_service
+    if let Some(winner) = winner {
+        ctx.accounts
+            .receipt_account
+            .as_mut()
+            .ok_or(ProgramErrorCode::MissingReceiptAccount)?
+            .record(
+                ctx.bumps.receipt_account.ok_or(ProgramErrorCode::MissingReceiptAccount)?,
+                winner,
+                ctx.accounts.seller.key(),
+                nft_mint,
+                payment_mint,
+                price,
+            )?;
+
+        vendor_profile.record_sale(payment_mint, price)?;
+    }
+
+    let service_account = ctx.accounts.service_account.deref_mut();
+    service_account.is_in_auction = false;
+    if let Some(winner) = winner {
+        service_account.change_vendor(winner);
+    }
+
+    emit!(SettleAuctionEvent {
+        seller: ctx.accounts.seller.key(),
+        winner,
+        nft_mint,
+        payment_mint,
+        price,
+        royalty_amount: sale_amounts.royalty_amount,
+        protocol_fee_amount: sale_amounts.protocol_fee_amount,
+        remaining_amount: sale_amounts.remaining_amount,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,115 @@
+use std::ops::DerefMut;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    error::ProgramErrorCode,
+    helper::has_expired,
+    states::{AuctionAccount, ConfigAccount, ServiceAccount, AUCTION_SEED, SERVICE_SEED},
+};
+
+#[derive(Accounts)]
+pub struct StartAuction<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    #[account(
+        constraint = !config_account.paused @ProgramErrorCode::MarketplacePaused
+    )]
+    pub config_account: Account<'info, ConfigAccount>,
+    #[account(
+        mut,
+        seeds = [&SERVICE_SEED.as_bytes(), service_account.nft_mint.as_ref()],
+        bump = service_account.bump,
+        constraint = service_account.current_vendor == seller.key() @ProgramErrorCode::InvalidCurrentVendor,
+        constraint = service_account.is_listed @ProgramErrorCode::ServiceNotListed,
+        constraint = service_account.is_in_custody @ProgramErrorCode::ServiceNotInCustody,
+        constraint = service_account.open_orders == 0 @ProgramErrorCode::ServiceHasOpenOrder,
+        constraint = !service_account.is_in_auction @ProgramErrorCode::ServiceInAuction,
+        // Auctions sell the whole listing, units are sold with buy_service
+        constraint = service_account.unit_mint.is_none() @ProgramErrorCode::InventoryAlreadyEnabled,
+        constraint = service_account.payment_mint == payment_mint.as_ref().map(|mint| mint.key()) @ProgramErrorCode::InvalidPaymentMint
+    )]
+    pub service_account: Account<'info, ServiceAccount>,
+    #[account(
+        init,
+        payer = seller,
+        space = AuctionAccount::INIT_SPACE,
+        seeds = [&AUCTION_SEED.as_bytes(), service_account.nft_mint.as_ref()],
+        bump
+    )]
+    pub auction_account: Account<'info, AuctionAccount>,
+
+    // The accounts below are only needed when the service is paid in an SPL token
+    pub payment_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        init_if_needed,
+        payer = seller,
+        associated_token::mint = payment_mint,
+        associated_token::authority = auction_account,
+        associated_token::token_program = token_program
+    )]
+    pub auction_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct StartAuctionEvent {
+    pub seller: Pubkey,
+    pub nft_mint: Pubkey,
+    pub payment_mint: Option<Pubkey>,
+    pub reserve_price: u64,
+    pub min_bid_increment: u64,
+    pub end_time: i64,
+}
+
+pub fn start_auction(
+    ctx: Context<StartAuction>,
+    reserve_price: u64,
+    min_bid_increment: u64,
+    duration: i64,
+) -> Result<()> {
+    if duration <= 0 {
+        return Err(ProgramErrorCode::InvalidTimeout.into());
+    }
+
+    if has_expired(ctx.accounts.service_account.expires_at)? {
+        return Err(ProgramErrorCode::ListingExpired.into());
+    }
+
+    let end_time = Clock::get()?
+        .unix_timestamp
+        .checked_add(duration)
+        .ok_or(ProgramErrorCode::Overflow)?;
+
+    let service_account = ctx.accounts.service_account.deref_mut();
+    service_account.is_in_auction = true;
+
+    let auction_account = ctx.accounts.auction_account.deref_mut();
+    auction_account.bump = ctx.bumps.auction_account;
+    auction_account.seller = ctx.accounts.seller.key();
+    auction_account.nft_mint = service_account.nft_mint;
+    auction_account.payment_mint = service_account.payment_mint;
+    auction_account.reserve_price = reserve_price;
+    auction_account.min_bid_increment = min_bid_increment;
+    auction_account.end_time = end_time;
+    auction_account.highest_bidder = None;
+    auction_account.highest_bid = 0;
+
+    emit!(StartAuctionEvent {
+        seller: auction_account.seller,
+        nft_mint: auction_account.nft_mint,
+        payment_mint: auction_account.payment_mint,
+        reserve_price,
+        min_bid_increment,
+        end_time,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use std::ops::DerefMut;
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::ProgramErrorCode,
+    states::{
+        PurchaseReceiptAccount, ServiceAccount, VendorProfileAccount, MAX_REVIEW_SCORE,
+        MIN_REVIEW_SCORE, RECEIPT_SEED, SERVICE_SEED, VENDOR_PROFILE_SEED,
+    },
+};
+
+#[derive(Accounts)]
+pub struct SubmitReview<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [&RECEIPT_SEED.as_bytes(), receipt_account.nft_mint.as_ref(), buyer.key().as_ref()],
+        bump = receipt_account.bump,
+        constraint = receipt_account.review_score == 0 @ProgramErrorCode::PurchaseAlreadyReviewed,
+        constraint = receipt_account.vendor != buyer.key() @ProgramErrorCode::CannotBuyOwnService
+    )]
+    pub receipt_account: Account<'info, PurchaseReceiptAccount>,
+    #[account(
+        mut,
+        seeds = [&SERVICE_SEED.as_bytes(), receipt_account.nft_mint.as_ref()],
+        bump = service_account.bump
+    )]
+    pub service_account: Account<'info, ServiceAccount>,
+    // The profile of the vendor who sold the service
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = VendorProfileAccount::INIT_SPACE,
+        seeds = [&VENDOR_PROFILE_SEED.as_bytes(), receipt_account.vendor.as_ref()],
+        bump
+    )]
+    pub vendor_profile: Account<'info, VendorProfileAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct SubmitReviewEvent {
+    pub buyer: Pubkey,
+    pub vendor: Pubkey,
+    pub nft_mint: Pubkey,
+    pub score: u8,
+    pub comment_hash: [u8; 32],
+}
+
+pub fn submit_review(ctx: Context<SubmitReview>, score: u8, comment_hash: [u8; 32]) -> Result<()> {
+    if !(MIN_REVIEW_SCORE..=MAX_REVIEW_SCORE).contains(&score) {
+        return Err(ProgramErrorCode::InvalidReviewScore.into());
+    }
+
+    let receipt_account = ctx.accounts.receipt_account.deref_mut();
+    receipt_account.review_score = score;
+    receipt_account.comment_hash = comment_hash;
+
+    let service_account = ctx.accounts.service_account.deref_mut();
+    service_account.rating.add(score)?;
+
+    let vendor_profile = ctx.accounts.vendor_profile.deref_mut();
+    vendor_profile.init_if_needed(ctx.bumps.vendor_profile, receipt_account.vendor);
+    vendor_profile.rating.add(score)?;
+
+    emit!(SubmitReviewEvent {
+        buyer: ctx.accounts.buyer.key(),
+        vendor: receipt_account.vendor,
+        nft_mint: receipt_account.nft_mint,
+        score,
+        comment_hash,
+    });
+
+    Ok(())
+}
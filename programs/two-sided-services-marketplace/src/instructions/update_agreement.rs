@@ -0,0 +1,97 @@
+use anchor_lang::{prelude::*, solana_program::program::invoke_signed};
+use anchor_spl::token_interface::{
+    spl_token_2022,
+    spl_token_metadata_interface::{self, state::Field},
+    Token2022,
+};
+
+use crate::{
+    constant::MAX_AGREEMENTS,
+    error::ProgramErrorCode,
+    helper::{read_token_metadata, top_up_rent, validate_agreement},
+    states::{ConfigAccount, ServiceAccount, CONFIG_SEED, SERVICE_SEED},
+};
+
+#[derive(Accounts)]
+pub struct UpdateAgreement<'info> {
+    #[account(mut)]
+    pub vendor: Signer<'info>,
+    #[account(
+        seeds = [&CONFIG_SEED.as_bytes()],
+        bump = config_account.bump
+    )]
+    pub config_account: Account<'info, ConfigAccount>,
+    #[account(
+        seeds = [&SERVICE_SEED.as_bytes(), service_account.nft_mint.as_ref()],
+        bump = service_account.bump,
+        constraint = service_account.current_vendor == vendor.key() @ProgramErrorCode::InvalidCurrentVendor,
+        constraint = service_account.is_in_custody @ProgramErrorCode::ServiceNotInCustody,
+        constraint = !service_account.is_in_auction @ProgramErrorCode::ServiceInAuction,
+        constraint = service_account.open_orders == 0 @ProgramErrorCode::ServiceHasOpenOrder
+    )]
+    pub service_account: Account<'info, ServiceAccount>,
+    /// CHECK: The service NFT mint, its metadata is updated by the token program.
+    #[account(
+        mut,
+        address = service_account.nft_mint @ProgramErrorCode::InvalidNftMint
+    )]
+    pub nft_mint: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token2022>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct UpdateAgreementEvent {
+    pub vendor: Pubkey,
+    pub nft_mint: Pubkey,
+    pub title: String,
+    pub details: String,
+}
+
+// Updates the details of an agreement, or adds it when the title is new
+pub fn update_agreement(ctx: Context<UpdateAgreement>, title: String, details: String) -> Result<()> {
+    validate_agreement(&title, &details)?;
+
+    let nft_mint = ctx.accounts.nft_mint.to_account_info();
+
+    let mut token_metadata = read_token_metadata(&nft_mint)?;
+    let old_metadata_len = token_metadata.tlv_size_of()?;
+    let is_new = !token_metadata
+        .additional_metadata
+        .iter()
+        .any(|(key, _)| *key == title);
+
+    if is_new && token_metadata.additional_metadata.len() >= MAX_AGREEMENTS {
+        return Err(ProgramErrorCode::TooManyAgreements.into());
+    }
+
+    token_metadata.update(Field::Key(title.clone()), details.clone());
+    let new_len = nft_mint.data_len() - old_metadata_len + token_metadata.tlv_size_of()?;
+    top_up_rent(ctx.accounts.vendor.to_account_info(), nft_mint.clone(), new_len)?;
+
+    // The config PDA is the metadata update authority
+    let signer: &[&[&[u8]]] = &[&[CONFIG_SEED.as_bytes(), &[ctx.accounts.config_account.bump]]];
+
+    invoke_signed(
+        &spl_token_metadata_interface::instruction::update_field(
+            &spl_token_2022::id(),
+            nft_mint.key,
+            &ctx.accounts.config_account.key(),
+            Field::Key(title.clone()),
+            details.clone(),
+        ),
+        &[nft_mint.clone(), ctx.accounts.config_account.to_account_info()],
+        signer,
+    )?;
+
+    emit!(UpdateAgreementEvent {
+        vendor: ctx.accounts.vendor.key(),
+        nft_mint: nft_mint.key(),
+        title,
+        details,
+    });
+
+    Ok(())
+}
@@ -1,8 +1,13 @@
 use std::ops::DerefMut;
 
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
-use crate::{error::ProgramErrorCode, helper::send_lamports, AskAccount};
+use crate::{
+    error::ProgramErrorCode,
+    helper::{optional_account_info, token_payment, transfer_payment},
+    AskAccount, ASK_SEED,
+};
 
 #[derive(Accounts)]
 pub struct UpdateAskPrice<'info> {
@@ -10,9 +15,31 @@ pub struct UpdateAskPrice<'info> {
     pub asker: Signer<'info>,
     #[account(
         mut,
-        constraint = ask_account.asker == asker.key()
+        seeds = [&ASK_SEED.as_bytes(), ask_account.nft_mint.as_ref(), asker.key().as_ref()],
+        bump = ask_account.bump,
+        constraint = ask_account.asker == asker.key(),
+        constraint = ask_account.payment_mint == payment_mint.as_ref().map(|mint| mint.key()) @ProgramErrorCode::InvalidPaymentMint
     )]
     pub ask_account: Account<'info, AskAccount>,
+
+    // The accounts below are only needed when the ask is paid in an SPL token
+    pub payment_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = asker,
+        token::token_program = token_program
+    )]
+    pub asker_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = ask_account,
+        associated_token::token_program = token_program
+    )]
+    pub ask_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
     pub system_program: Program<'info, System>,
 }
 
@@ -26,35 +53,49 @@ pub struct UpdateAskPriceEvent {
 
 pub fn update_ask_price(ctx: Context<UpdateAskPrice>, new_ask_price: u64) -> Result<()> {
     let ask_account = &ctx.accounts.ask_account;
+    let token_payment = token_payment(&ctx.accounts.payment_mint, &ctx.accounts.token_program)?;
 
     let old_ask_price = ask_account.ask_price;
 
     // Determine the difference in price
     if new_ask_price > old_ask_price {
-        // If the new price is higher, transfer additional lamports from the asker to the ask account
-        let additional_lamports = new_ask_price
+        // If the new price is higher, transfer the difference from the asker to the ask account
+        let additional_amount = new_ask_price
             .checked_sub(old_ask_price)
             .ok_or(ProgramErrorCode::Overflow)?;
-        send_lamports(
+        transfer_payment(
             ctx.accounts.asker.to_account_info(),
+            optional_account_info(&ctx.accounts.asker_token_account),
             ctx.accounts.ask_account.to_account_info(),
-            additional_lamports,
+            optional_account_info(&ctx.accounts.ask_token_account),
+            token_payment.as_ref(),
+            additional_amount,
+            None,
         )?;
     } else if new_ask_price < old_ask_price {
         // If the new price is lower, refund the difference from the ask account to the asker
-        let refund_lamports = old_ask_price
+        let refund_amount = old_ask_price
             .checked_sub(new_ask_price)
             .ok_or(ProgramErrorCode::Overflow)?;
-        **ctx
-            .accounts
-            .ask_account
-            .to_account_info()
-            .try_borrow_mut_lamports()? -= refund_lamports;
-        **ctx
-            .accounts
-            .asker
-            .to_account_info()
-            .try_borrow_mut_lamports()? += refund_lamports;
+
+        let nft_mint = ask_account.nft_mint;
+        let asker = ask_account.asker;
+        let signer: &[&[&[u8]]] = &[&[
+            ASK_SEED.as_bytes(),
+            nft_mint.as_ref(),
+            asker.as_ref(),
+            &[ask_account.bump],
+        ]];
+
+        transfer_payment(
+            ctx.accounts.ask_account.to_account_info(),
+            optional_account_info(&ctx.accounts.ask_token_account),
+            ctx.accounts.asker.to_account_info(),
+            optional_account_info(&ctx.accounts.asker_token_account),
+            token_payment.as_ref(),
+            refund_amount,
+            Some(signer),
+        )?;
     }
 
     let ask_account = ctx.accounts.ask_account.deref_mut();
@@ -27,7 +27,7 @@ pub fn update_service_price(ctx: Context<UpdateServicePrice>, new_price: u64) ->
     let service_account = ctx.accounts.service_account.deref_mut();
 
     let old_price = service_account.price;
-    service_account.price = new_price;
+    service_account.set_price(new_price)?;
 
     emit!(UpdateServicePriceEvent {
         vendor: ctx.accounts.vendor.key(),
@@ -0,0 +1,60 @@
+use std::ops::DerefMut;
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::ProgramErrorCode,
+    states::{
+        VendorProfileAccount, MAX_CONTACT_URI_LENGTH, MAX_DISPLAY_NAME_LENGTH, VENDOR_PROFILE_SEED,
+    },
+};
+
+#[derive(Accounts)]
+pub struct UpdateVendorProfile<'info> {
+    #[account(mut)]
+    pub vendor: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = vendor,
+        space = VendorProfileAccount::INIT_SPACE,
+        seeds = [&VENDOR_PROFILE_SEED.as_bytes(), vendor.key().as_ref()],
+        bump
+    )]
+    pub vendor_profile: Account<'info, VendorProfileAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct UpdateVendorProfileEvent {
+    pub vendor: Pubkey,
+    pub display_name: String,
+    pub contact_uri: String,
+}
+
+pub fn update_vendor_profile(
+    ctx: Context<UpdateVendorProfile>,
+    display_name: String,
+    contact_uri: String,
+) -> Result<()> {
+    if display_name.len() > MAX_DISPLAY_NAME_LENGTH {
+        return Err(ProgramErrorCode::DisplayNameTooLong.into());
+    }
+    if contact_uri.len() > MAX_CONTACT_URI_LENGTH {
+        return Err(ProgramErrorCode::ContactUriTooLong.into());
+    }
+
+    let vendor = ctx.accounts.vendor.key();
+    let vendor_profile = ctx.accounts.vendor_profile.deref_mut();
+    vendor_profile.init_if_needed(ctx.bumps.vendor_profile, vendor);
+    vendor_profile.display_name = display_name.clone();
+    vendor_profile.contact_uri = contact_uri.clone();
+
+    emit!(UpdateVendorProfileEvent {
+        vendor,
+        display_name,
+        contact_uri,
+    });
+
+    Ok(())
+}
@@ -1,3 +1,5 @@
+use std::ops::DerefMut;
+
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token_2022::Token2022;
@@ -16,7 +18,12 @@ pub struct WithdrawNFTService<'info> {
     pub vendor: Signer<'info>,
     #[account(mut)]
     pub config_account: Account<'info, ConfigAccount>,
-    #[account(mut)]
+    #[account(
+        mut,
+        associated_token::mint = nft_mint,
+        associated_token::authority = config_account,
+        associated_token::token_program = token_program
+    )]
     pub config_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
     #[account(mut)]
     pub nft_mint: Box<InterfaceAccount<'info, Mint>>,
@@ -24,13 +31,17 @@ pub struct WithdrawNFTService<'info> {
         init_if_needed,
         payer = vendor,
         associated_token::mint = nft_mint,
-        associated_token::authority = vendor
+        associated_token::authority = vendor,
+        associated_token::token_program = token_program
     )]
     pub vendor_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
     #[account(
      mut,
      constraint = nft_mint.key() == service_account.nft_mint,
-     constraint = vendor.key() == service_account.current_vendor
+     constraint = vendor.key() == service_account.current_vendor,
+     constraint = service_account.is_in_custody @ProgramErrorCode::ServiceNotInCustody,
+     constraint = service_account.open_orders == 0 @ProgramErrorCode::ServiceHasOpenOrder,
+     constraint = !service_account.is_in_auction @ProgramErrorCode::ServiceInAuction
   )]
     pub service_account: Account<'info, ServiceAccount>,
     pub token_program: Program<'info, Token2022>,
@@ -41,6 +52,12 @@ pub struct WithdrawNFTService<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[event]
+pub struct WithdrawServiceEvent {
+    pub vendor: Pubkey,
+    pub nft_mint: Pubkey,
+}
+
 pub fn withdraw_service(ctx: Context<WithdrawNFTService>) -> Result<()> {
     let service_account = &ctx.accounts.service_account;
 
@@ -58,10 +75,19 @@ pub fn withdraw_service(ctx: Context<WithdrawNFTService>) -> Result<()> {
         ctx.accounts.config_token_account.to_account_info(),
         ctx.accounts.vendor_token_account.to_account_info(),
         ctx.accounts.config_account.to_account_info(),
-        ctx.accounts.nft_mint.clone(),
+        &ctx.accounts.nft_mint,
         ctx.accounts.token_program.to_account_info(),
         signer,
     )?;
 
+    // Trading stays blocked until the NFT is deposited back
+    let service_account = ctx.accounts.service_account.deref_mut();
+    service_account.is_in_custody = false;
+
+    emit!(WithdrawServiceEvent {
+        vendor: ctx.accounts.vendor.key(),
+        nft_mint: ctx.accounts.nft_mint.key(),
+    });
+
     Ok(())
 }
@@ -15,8 +15,107 @@ pub mod two_sided_services_marketplace {
 
     use super::*;
 
-    pub fn initialize(ctx: Context<Initialize>, royalty_fee_basis_points: u16) -> Result<()> {
-        instructions::initialize(ctx, royalty_fee_basis_points)?;
+    pub fn initialize(
+        ctx: Context<Initialize>,
+        royalty_fee_basis_points: u16,
+        max_royalty_fee_basis_points: u16,
+        protocol_fee_basis_points: u16,
+    ) -> Result<()> {
+        instructions::initialize(
+            ctx,
+            royalty_fee_basis_points,
+            max_royalty_fee_basis_points,
+            protocol_fee_basis_points,
+        )?;
+        Ok(())
+    }
+
+    pub fn update_royalty(
+        ctx: Context<UpdateRoyalty>,
+        new_royalty_fee_basis_points: u16,
+    ) -> Result<()> {
+        instructions::update_royalty(ctx, new_royalty_fee_basis_points)?;
+        Ok(())
+    }
+
+    pub fn update_max_royalty(
+        ctx: Context<UpdateMaxRoyalty>,
+        new_max_royalty_fee_basis_points: u16,
+    ) -> Result<()> {
+        instructions::update_max_royalty(ctx, new_max_royalty_fee_basis_points)?;
+        Ok(())
+    }
+
+    pub fn update_protocol_fee(
+        ctx: Context<UpdateProtocolFee>,
+        new_protocol_fee_basis_points: u16,
+    ) -> Result<()> {
+        instructions::update_protocol_fee(ctx, new_protocol_fee_basis_points)?;
+        Ok(())
+    }
+
+    pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+        instructions::withdraw_treasury(ctx, amount)?;
+        Ok(())
+    }
+
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        instructions::set_paused(ctx, paused)?;
+        Ok(())
+    }
+
+    pub fn set_payment_mint(ctx: Context<SetPaymentMint>, allowed: bool) -> Result<()> {
+        instructions::set_payment_mint(ctx, allowed)?;
+        Ok(())
+    }
+
+    pub fn update_order_refund_timeout(
+        ctx: Context<UpdateOrderRefundTimeout>,
+        new_order_refund_timeout: i64,
+    ) -> Result<()> {
+        instructions::update_order_refund_timeout(ctx, new_order_refund_timeout)?;
+        Ok(())
+    }
+
+    pub fn update_default_uri_template(
+        ctx: Context<UpdateDefaultUriTemplate>,
+        new_default_uri_template: String,
+    ) -> Result<()> {
+        instructions::update_default_uri_template(ctx, new_default_uri_template)?;
+        Ok(())
+    }
+
+    pub fn set_arbiter(ctx: Context<SetArbiter>, arbiter: Pubkey, allowed: bool) -> Result<()> {
+        instructions::set_arbiter(ctx, arbiter, allowed)?;
+        Ok(())
+    }
+
+    pub fn set_vendor_verified(ctx: Context<SetVendorVerified>, is_verified: bool) -> Result<()> {
+        instructions::set_vendor_verified(ctx, is_verified)?;
+        Ok(())
+    }
+
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        instructions::propose_admin(ctx, new_admin)?;
+        Ok(())
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        instructions::accept_admin(ctx)?;
+        Ok(())
+    }
+
+    pub fn cancel_admin_proposal(ctx: Context<CancelAdminProposal>) -> Result<()> {
+        instructions::cancel_admin_proposal(ctx)?;
+        Ok(())
+    }
+
+    pub fn update_vendor_profile(
+        ctx: Context<UpdateVendorProfile>,
+        display_name: String,
+        contact_uri: String,
+    ) -> Result<()> {
+        instructions::update_vendor_profile(ctx, display_name, contact_uri)?;
         Ok(())
     }
 
@@ -25,23 +124,142 @@ pub mod two_sided_services_marketplace {
         is_soulbound: bool,
         agreements: Vec<ServiceAgreement>,
         price: u64,
+        royalty_basis_points: Option<u16>,
+        creators: Vec<Creator>,
+        metadata: ServiceMetadata,
     ) -> Result<()> {
-        instructions::create_service(ctx, is_soulbound, agreements, price)?;
+        instructions::create_service(
+            ctx,
+            is_soulbound,
+            agreements,
+            price,
+            royalty_basis_points,
+            creators,
+            metadata,
+        )?;
         Ok(())
     }
 
-    pub fn buy_service(ctx: Context<BuyService>) -> Result<()> {
-        instructions::buy_service(ctx)?;
+    pub fn update_agreement(
+        ctx: Context<UpdateAgreement>,
+        title: String,
+        details: String,
+    ) -> Result<()> {
+        instructions::update_agreement(ctx, title, details)?;
+        Ok(())
+    }
+
+    pub fn remove_agreement(ctx: Context<RemoveAgreement>, title: String) -> Result<()> {
+        instructions::remove_agreement(ctx, title)?;
+        Ok(())
+    }
+
+    pub fn buy_service<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, BuyService<'info>>,
+        max_price: u64,
+    ) -> Result<()> {
+        instructions::buy_service(ctx, max_price)?;
         Ok(())
     }
 
-    pub fn ask_service(ctx: Context<AskService>, ask_price: u64) -> Result<()> {
-        instructions::ask_service(ctx, ask_price)?;
+    pub fn purchase_order(ctx: Context<PurchaseOrder>, max_price: u64) -> Result<()> {
+        instructions::purchase_order(ctx, max_price)?;
         Ok(())
     }
 
-    pub fn accept_ask(ctx: Context<AccpectAsk>) -> Result<()> {
-        instructions::accept_ask(ctx)?;
+    pub fn confirm_delivery<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, ConfirmDelivery<'info>>,
+    ) -> Result<()> {
+        instructions::confirm_delivery(ctx)?;
+        Ok(())
+    }
+
+    pub fn request_milestone_release(ctx: Context<RequestMilestoneRelease>) -> Result<()> {
+        instructions::request_milestone_release(ctx)?;
+        Ok(())
+    }
+
+    pub fn approve_milestone_release<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, ApproveMilestoneRelease<'info>>,
+    ) -> Result<()> {
+        instructions::approve_milestone_release(ctx)?;
+        Ok(())
+    }
+
+    pub fn refund_order(ctx: Context<RefundOrder>) -> Result<()> {
+        instructions::refund_order(ctx)?;
+        Ok(())
+    }
+
+    pub fn open_dispute(ctx: Context<OpenDispute>) -> Result<()> {
+        instructions::open_dispute(ctx)?;
+        Ok(())
+    }
+
+    pub fn resolve_dispute<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, ResolveDispute<'info>>,
+        buyer_share_basis_points: u16,
+    ) -> Result<()> {
+        instructions::resolve_dispute(ctx, buyer_share_basis_points)?;
+        Ok(())
+    }
+
+    pub fn submit_review(ctx: Context<SubmitReview>, score: u8, comment_hash: [u8; 32]) -> Result<()> {
+        instructions::submit_review(ctx, score, comment_hash)?;
+        Ok(())
+    }
+
+    pub fn start_auction(
+        ctx: Context<StartAuction>,
+        reserve_price: u64,
+        min_bid_increment: u64,
+        duration: i64,
+    ) -> Result<()> {
+        instructions::start_auction(ctx, reserve_price, min_bid_increment, duration)?;
+        Ok(())
+    }
+
+    pub fn place_bid(ctx: Context<PlaceBid>, amount: u64) -> Result<()> {
+        instructions::place_bid(ctx, amount)?;
+        Ok(())
+    }
+
+    pub fn settle_auction<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, SettleAuction<'info>>,
+    ) -> Result<()> {
+        instructions::settle_auction(ctx)?;
+        Ok(())
+    }
+
+    pub fn cancel_auction(ctx: Context<CancelAuction>) -> Result<()> {
+        instructions::cancel_auction(ctx)?;
+        Ok(())
+    }
+
+    pub fn ask_service(
+        ctx: Context<AskService>,
+        ask_price: u64,
+        expires_at: Option<i64>,
+    ) -> Result<()> {
+        instructions::ask_service(ctx, ask_price, expires_at)?;
+        Ok(())
+    }
+
+    pub fn cancel_ask(ctx: Context<CancelAsk>) -> Result<()> {
+        instructions::cancel_ask(ctx)?;
+        Ok(())
+    }
+
+    pub fn cancel_expired_ask(ctx: Context<CancelExpiredAsk>) -> Result<()> {
+        instructions::cancel_expired_ask(ctx)?;
+        Ok(())
+    }
+
+    pub fn accept_ask<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, AccpectAsk<'info>>,
+        min_price: u64,
+    ) -> Result<()> {
+        instructions::accept_ask(ctx, min_price)?;
         Ok(())
     }
 
@@ -50,6 +268,18 @@ pub mod two_sided_services_marketplace {
         Ok(())
     }
 
+    pub fn deposit_service(ctx: Context<DepositNFTService>) -> Result<()> {
+        instructions::deposit_service(ctx)?;
+        Ok(())
+    }
+
+    pub fn close_service<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, CloseService<'info>>,
+    ) -> Result<()> {
+        instructions::close_service(ctx)?;
+        Ok(())
+    }
+
     pub fn update_ask_price(ctx: Context<UpdateAskPrice>, new_ask_price: u64) -> Result<()> {
         instructions::update_ask_price(ctx, new_ask_price)?;
         Ok(())
@@ -59,4 +289,32 @@ pub mod two_sided_services_marketplace {
         instructions::update_service_price(ctx, new_service_price)?;
         Ok(())
     }
+
+    pub fn set_service_listed(ctx: Context<SetServiceListed>, is_listed: bool) -> Result<()> {
+        instructions::set_service_listed(ctx, is_listed)?;
+        Ok(())
+    }
+
+    pub fn enable_inventory(ctx: Context<EnableInventory>, capacity: u32) -> Result<()> {
+        instructions::enable_inventory(ctx, capacity)?;
+        Ok(())
+    }
+
+    pub fn restock_service(ctx: Context<RestockService>, units: u32) -> Result<()> {
+        instructions::restock_service(ctx, units)?;
+        Ok(())
+    }
+
+    pub fn set_dutch_auction(
+        ctx: Context<SetDutchAuction>,
+        dutch_auction: Option<DutchAuction>,
+    ) -> Result<()> {
+        instructions::set_dutch_auction(ctx, dutch_auction)?;
+        Ok(())
+    }
+
+    pub fn set_service_expiry(ctx: Context<SetServiceExpiry>, expires_at: Option<i64>) -> Result<()> {
+        instructions::set_service_expiry(ctx, expires_at)?;
+        Ok(())
+    }
 }
@@ -8,6 +8,9 @@ pub struct AskAccount {
     pub asker: Pubkey,
     pub nft_mint: Pubkey,
     pub ask_price: u64,
+    pub payment_mint: Option<Pubkey>,
+    // Anyone can cancel the ask from this unix timestamp
+    pub expires_at: Option<i64>,
 }
 
 impl Space for AskAccount {
@@ -15,5 +18,7 @@ impl Space for AskAccount {
         + 1  // bump
         + 32 // asker
         + 32 // nft_mint
-        + 8; // price
+        + 8 // price
+        + 1 + 32 // payment_mint
+        + 1 + 8; // expires_at
 }
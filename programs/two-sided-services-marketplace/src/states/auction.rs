@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+pub const AUCTION_SEED: &str = "auction";
+
+#[account]
+pub struct AuctionAccount {
+    pub bump: u8,
+    pub seller: Pubkey,
+    pub nft_mint: Pubkey,
+    pub payment_mint: Option<Pubkey>,
+    // The first bid has to reach the reserve price
+    pub reserve_price: u64,
+    // Every following bid has to beat the highest bid by at least this amount
+    pub min_bid_increment: u64,
+    pub end_time: i64,
+    // The highest bid is held in escrow by this account until it is outbid or settled
+    pub highest_bidder: Option<Pubkey>,
+    pub highest_bid: u64,
+}
+
+impl Space for AuctionAccount {
+    const INIT_SPACE: usize = 8  // Account discriminator added by Anchor for each account
+        + 1  // bump
+        + 32 // seller
+        + 32 // nft_mint
+        + 1 + 32 // payment_mint
+        + 8 // reserve_price
+        + 8 // min_bid_increment
+        + 8 // end_time
+        + 1 + 32 // highest_bidder
+        + 8; // highest_bid
+}
@@ -1,12 +1,23 @@
 use anchor_lang::prelude::*;
 
+use crate::constant::{MAX_ARBITERS, MAX_PAYMENT_MINTS, MAX_URI_LENGTH};
+
 pub const CONFIG_SEED: &str = "config";
 #[account]
 pub struct ConfigAccount {
   pub bump: u8,
   pub is_initialized: bool,
   pub admin: Pubkey,
-  pub royalty_fee_basis_points: u16
+  pub royalty_fee_basis_points: u16,
+  pub pending_admin: Option<Pubkey>,
+  pub protocol_fee_basis_points: u16,
+  pub paused: bool,
+  pub payment_mints: Vec<Pubkey>,
+  pub max_royalty_fee_basis_points: u16,
+  pub order_refund_timeout: i64,
+  pub arbiters: Vec<Pubkey>,
+  // Used when a service is created without a URI, `{mint}` is replaced by the NFT mint
+  pub default_uri_template: String,
 }
 
 impl Space for ConfigAccount {
@@ -14,5 +25,13 @@ impl Space for ConfigAccount {
         + 1 // bump
         + 1 //is_initialized
         + 32 // admin
-        + 2; //royalty_fee_basis_points
-}
\ No newline at end of file
+        + 2 //royalty_fee_basis_points
+        + 1 + 32 // pending_admin
+        + 2 // protocol_fee_basis_points
+        + 1 // paused
+        + 4 + 32 * MAX_PAYMENT_MINTS // payment_mints
+        + 2 // max_royalty_fee_basis_points
+        + 8 // order_refund_timeout
+        + 4 + 32 * MAX_ARBITERS // arbiters
+        + 4 + MAX_URI_LENGTH; // default_uri_template
+}
@@ -5,4 +5,19 @@ pub mod service;
 pub use service::*;
 
 pub mod ask;
-pub use ask::*;
\ No newline at end of file
+pub use ask::*;
+
+pub mod treasury;
+pub use treasury::*;
+
+pub mod order;
+pub use order::*;
+
+pub mod receipt;
+pub use receipt::*;
+
+pub mod vendor_profile;
+pub use vendor_profile::*;
+
+pub mod auction;
+pub use auction::*;
\ No newline at end of file
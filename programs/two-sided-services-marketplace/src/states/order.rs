@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+
+use crate::{constant::MAX_AGREEMENTS, error::ProgramErrorCode};
+
+pub const ORDER_SEED: &str = "order";
+
+#[account]
+pub struct OrderAccount {
+    pub bump: u8,
+    pub buyer: Pubkey,
+    pub vendor: Pubkey,
+    pub nft_mint: Pubkey,
+    pub payment_mint: Option<Pubkey>,
+    pub amount: u64,
+    pub created_at: i64,
+    // The buyer can take the payment back from this time on if delivery was never confirmed
+    pub refundable_at: i64,
+    // Copied from the service when the order is placed
+    pub milestone_amounts: Vec<u64>,
+    pub released_milestones: u8,
+    pub released_amount: u64,
+    pub release_requested: bool,
+    pub is_disputed: bool,
+}
+
+impl Space for OrderAccount {
+    const INIT_SPACE: usize = 8  // Account discriminator added by Anchor for each account
+        + 1  // bump
+        + 32 // buyer
+        + 32 // vendor
+        + 32 // nft_mint
+        + 1 + 32 // payment_mint
+        + 8 // amount
+        + 8 // created_at
+        + 8 // refundable_at
+        + 4 + 8 * MAX_AGREEMENTS // milestone_amounts
+        + 1 // released_milestones
+        + 8 // released_amount
+        + 1 // release_requested
+        + 1; // is_disputed
+}
+
+impl OrderAccount {
+    pub fn remaining_amount(&self) -> Result<u64> {
+        self.amount
+            .checked_sub(self.released_amount)
+            .ok_or(ProgramErrorCode::Overflow.into())
+    }
+
+    pub fn next_milestone_amount(&self) -> Option<u64> {
+        self.milestone_amounts
+            .get(self.released_milestones as usize)
+            .copied()
+    }
+}
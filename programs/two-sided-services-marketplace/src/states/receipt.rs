@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ProgramErrorCode;
+
+pub const RECEIPT_SEED: &str = "receipt";
+
+pub const MIN_REVIEW_SCORE: u8 = 1;
+pub const MAX_REVIEW_SCORE: u8 = 5;
+
+#[account]
+pub struct PurchaseReceiptAccount {
+    pub bump: u8,
+    pub buyer: Pubkey,
+    pub vendor: Pubkey,
+    pub nft_mint: Pubkey,
+    pub payment_mint: Option<Pubkey>,
+    pub price: u64,
+    pub purchased_at: i64,
+    // 0 until the buyer reviews the purchase
+    pub review_score: u8,
+    pub comment_hash: [u8; 32],
+}
+
+impl Space for PurchaseReceiptAccount {
+    const INIT_SPACE: usize = 8  // Account discriminator added by Anchor for each account
+        + 1  // bump
+        + 32 // buyer
+        + 32 // vendor
+        + 32 // nft_mint
+        + 1 + 32 // payment_mint
+        + 8 // price
+        + 8 // purchased_at
+        + 1 // review_score
+        + 32; // comment_hash
+}
+
+impl PurchaseReceiptAccount {
+    // A repeated purchase updates the receipt, but a buyer reviews a service only once
+    pub fn record(
+        &mut self,
+        bump: u8,
+        buyer: Pubkey,
+        vendor: Pubkey,
+        nft_mint: Pubkey,
+        payment_mint: Option<Pubkey>,
+        price: u64,
+    ) -> Result<()> {
+        self.bump = bump;
+        self.buyer = buyer;
+        self.vendor = vendor;
+        self.nft_mint = nft_mint;
+        self.payment_mint = payment_mint;
+        self.price = price;
+        self.purchased_at = Clock::get()?.unix_timestamp;
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct RatingStats {
+    pub count: u32,
+    pub total: u64,
+    // Average score in hundredths, 450 is 4.5
+    pub average: u16,
+}
+
+impl RatingStats {
+    pub const SPACE: usize = 4 // count
+        + 8 // total
+        + 2; // average
+
+    pub fn add(&mut self, score: u8) -> Result<()> {
+        self.count = self.count.checked_add(1).ok_or(ProgramErrorCode::Overflow)?;
+        self.total = self
+            .total
+            .checked_add(score as u64)
+            .ok_or(ProgramErrorCode::Overflow)?;
+        self.average = (self.total * 100 / self.count as u64) as u16;
+        Ok(())
+    }
+}
@@ -1,11 +1,89 @@
 use anchor_lang::prelude::*;
 
+use crate::{
+    constant::{MAX_AGREEMENTS, MAX_CREATORS},
+    error::ProgramErrorCode,
+    states::RatingStats,
+};
+
 pub const SERVICE_SEED: &str = "service";
+pub const UNIT_MINT_SEED: &str = "unit_mint";
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct ServiceAgreement {
     pub title: String,
     pub details: String,
+    // Part of the price released when this agreement is delivered
+    pub amount: u64,
+}
+
+// Written to the Token-2022 metadata of the service NFT
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ServiceMetadata {
+    pub name: String,
+    pub symbol: String,
+    // Leave empty to use the default URI template of the config
+    pub uri: Option<String>,
+}
+
+// The price falls from start_price to floor_price between start_time and end_time
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct DutchAuction {
+    pub start_price: u64,
+    pub floor_price: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    // Length of each price step in seconds, 0 for a linear decline
+    pub step_duration: i64,
+}
+
+impl DutchAuction {
+    pub const SPACE: usize = 8 // start_price
+        + 8 // floor_price
+        + 8 // start_time
+        + 8 // end_time
+        + 8; // step_duration
+
+    pub fn validate(&self) -> Result<()> {
+        // Vendor supplied timestamps can be far enough apart to overflow
+        let duration = self
+            .end_time
+            .checked_sub(self.start_time)
+            .ok_or(ProgramErrorCode::InvalidDutchAuction)?;
+        if self.start_price < self.floor_price
+            || duration <= 0
+            || self.step_duration < 0
+            || self.step_duration > duration
+        {
+            return Err(ProgramErrorCode::InvalidDutchAuction.into());
+        }
+        Ok(())
+    }
+
+    pub fn price_at(&self, now: i64) -> u64 {
+        if now <= self.start_time {
+            return self.start_price;
+        }
+        if now >= self.end_time {
+            return self.floor_price;
+        }
+
+        let mut elapsed = now - self.start_time;
+        if self.step_duration > 0 {
+            elapsed -= elapsed % self.step_duration;
+        }
+
+        let decline = (self.start_price - self.floor_price) as u128 * elapsed as u128
+            / (self.end_time - self.start_time) as u128;
+        self.start_price - decline as u64
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct Creator {
+    pub address: Pubkey,
+    // Percentage of the royalty paid to this creator
+    pub share: u8,
 }
 
 #[account]
@@ -16,6 +94,30 @@ pub struct ServiceAccount {
     pub current_vendor: Pubkey,
     pub nft_mint: Pubkey,
     pub price: u64,
+    pub is_listed: bool,
+    pub payment_mint: Option<Pubkey>,
+    pub royalty_basis_points: u16,
+    pub creators: Vec<Creator>,
+    // False while the NFT is withdrawn from config_token_account, trading is blocked until it is deposited back
+    pub is_in_custody: bool,
+    // Escrowed orders waiting for delivery, the service cannot change hands while one is open
+    pub open_orders: u32,
+    // One amount per agreement, empty when the service is paid in a single release
+    pub milestone_amounts: Vec<u64>,
+    pub rating: RatingStats,
+    // Asks escrowed against this service, they have to be refunded before it is closed
+    pub open_asks: u32,
+    // The listing stops accepting purchases at this unix timestamp
+    pub expires_at: Option<i64>,
+    // Set while an English auction is running, fixed price sales and orders are blocked
+    pub is_in_auction: bool,
+    // When set, buy_service charges the declining price instead of the fixed price
+    pub dutch_auction: Option<DutchAuction>,
+    // Set for services sold many times, each purchase mints one unit token to the buyer
+    pub unit_mint: Option<Pubkey>,
+    // Units made available since inventory was enabled
+    pub capacity: u32,
+    pub remaining_units: u32,
 }
 
 impl Space for ServiceAccount {
@@ -25,5 +127,74 @@ impl Space for ServiceAccount {
         + 32 // original_vendor
         + 32 // current_vendor
         + 32 // nft_mint
-        + 8; // price
+        + 8 // price
+        + 1 // is_listed
+        + 1 + 32 // payment_mint
+        + 2 // royalty_basis_points
+        + 4 + (32 + 1) * MAX_CREATORS // creators
+        + 1 // is_in_custody
+        + 4 // open_orders
+        + 4 + 8 * MAX_AGREEMENTS // milestone_amounts
+        + RatingStats::SPACE // rating
+        + 4 // open_asks
+        + 1 + 8 // expires_at
+        + 1 // is_in_auction
+        + 1 + DutchAuction::SPACE // dutch_auction
+        + 1 + 32 // unit_mint
+        + 4 // capacity
+        + 4; // remaining_units
+}
+
+impl ServiceAccount {
+    // Price charged by buy_service at the given time
+    pub fn current_price(&self, now: i64) -> u64 {
+        match &self.dutch_auction {
+            Some(dutch_auction) => dutch_auction.price_at(now),
+            None => self.price,
+        }
+    }
+
+    // Listing terms belong to the vendor who set them and are dropped when the service changes hands
+    pub fn change_vendor(&mut self, new_vendor: Pubkey) {
+        if self.current_vendor != new_vendor {
+            self.expires_at = None;
+            self.dutch_auction = None;
+        }
+        self.current_vendor = new_vendor;
+    }
+
+    // Royalties are only paid on resales, not when the original vendor sells
+    pub fn resale_royalty_basis_points(&self) -> u16 {
+        if self.current_vendor == self.original_vendor {
+            0
+        } else {
+            self.royalty_basis_points
+        }
+    }
+
+    // Milestones keep their proportions when the price changes, the last one absorbs rounding
+    pub fn set_price(&mut self, new_price: u64) -> Result<()> {
+        let old_price = self.price;
+        self.price = new_price;
+
+        let Some((last, others)) = self.milestone_amounts.split_last_mut() else {
+            return Ok(());
+        };
+
+        let mut distributed: u64 = 0;
+        for amount in others.iter_mut() {
+            *amount = match old_price {
+                0 => 0,
+                _ => ((*amount as u128) * (new_price as u128) / (old_price as u128)) as u64,
+            };
+            distributed = distributed
+                .checked_add(*amount)
+                .ok_or(ProgramErrorCode::Overflow)?;
+        }
+        *last = new_price
+            .checked_sub(distributed)
+            .ok_or(ProgramErrorCode::Overflow)?;
+
+        Ok(())
+    }
 }
@@ -0,0 +1,13 @@
+use anchor_lang::prelude::*;
+
+pub const TREASURY_SEED: &str = "treasury";
+
+#[account]
+pub struct TreasuryAccount {
+    pub bump: u8,
+}
+
+impl Space for TreasuryAccount {
+    const INIT_SPACE: usize = 8  // Account discriminator added by Anchor for each account
+        + 1; // bump
+}
@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ProgramErrorCode, states::RatingStats};
+
+pub const VENDOR_PROFILE_SEED: &str = "vendor_profile";
+
+pub const MAX_DISPLAY_NAME_LENGTH: usize = 32;
+pub const MAX_CONTACT_URI_LENGTH: usize = 200;
+
+#[account]
+pub struct VendorProfileAccount {
+    pub bump: u8,
+    pub vendor: Pubkey,
+    pub display_name: String,
+    pub contact_uri: String,
+    // Set by the admin once the vendor's identity has been checked
+    pub is_verified: bool,
+    // Services created by this vendor
+    pub service_count: u32,
+    // Services sold by this vendor, in any payment mint
+    pub sales_count: u64,
+    // Sum of the sale prices paid in SOL, in lamports. Token amounts of different mints
+    // cannot be added up, sales paid in an SPL token are counted in `token_sales_count`
+    pub sales_volume: u64,
+    // Sales paid in an SPL token, also included in `sales_count`
+    pub token_sales_count: u64,
+    // Reviews of every service sold by this vendor
+    pub rating: RatingStats,
+}
+
+impl Space for VendorProfileAccount {
+    const INIT_SPACE: usize = 8  // Account discriminator added by Anchor for each account
+        + 1  // bump
+        + 32 // vendor
+        + 4 + MAX_DISPLAY_NAME_LENGTH // display_name
+        + 4 + MAX_CONTACT_URI_LENGTH // contact_uri
+        + 1 // is_verified
+        + 4 // service_count
+        + 8 // sales_count
+        + 8 // sales_volume
+        + 8 // token_sales_count
+        + RatingStats::SPACE; // rating
+}
+
+impl VendorProfileAccount {
+    // Profiles are created on demand by the first instruction that touches the vendor
+    pub fn init_if_needed(&mut self, bump: u8, vendor: Pubkey) {
+        self.bump = bump;
+        self.vendor = vendor;
+    }
+
+    pub fn record_sale(&mut self, payment_mint: Option<Pubkey>, price: u64) -> Result<()> {
+        self.sales_count = self
+            .sales_count
+            .checked_add(1)
+            .ok_or(ProgramErrorCode::Overflow)?;
+        match payment_mint {
+            Some(_) => {
+                self.token_sales_count = self
+                    .token_sales_count
+                    .checked_add(1)
+                    .ok_or(ProgramErrorCode::Overflow)?;
+            }
+            None => {
+                self.sales_volume = self
+                    .sales_volume
+                    .checked_add(price)
+                    .ok_or(ProgramErrorCode::Overflow)?;
+            }
+        }
+        Ok(())
+    }
+}
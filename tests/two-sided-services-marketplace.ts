@@ -4,7 +4,7 @@ import { expect } from "chai";
 
 import { TwoSidedServicesMarketplace } from "../target/types/two_sided_services_marketplace";
 import { Keypair, PublicKey, Connection, Signer, LAMPORTS_PER_SOL } from "@solana/web3.js";
-import { ASSOCIATED_TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID, getAssociatedTokenAddressSync } from "@solana/spl-token";
+import { ASSOCIATED_TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID, getAssociatedTokenAddressSync, createMint, createAssociatedTokenAccount, mintTo, getAccount, getTokenMetadata, ExtensionType, getMintLen, createInitializeTransferFeeConfigInstruction, createInitializeMintInstruction } from "@solana/spl-token";
 import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
 
 describe("two-sided-services-marketplace", () => {
@@ -15,16 +15,25 @@ describe("two-sided-services-marketplace", () => {
 
   let admin: Signer;
   let vendor: Signer;
+  let teammate: Signer;
   let buyer: Signer;
   let asker: Signer;
+  let otherAsker: Signer;
 
   let serviceAccount: PublicKey;
   let askAccount: PublicKey;
+  let otherAskAccount: PublicKey;
   let configTokenAccount: PublicKey;
   let nftMint: Keypair;
 
   const royaltyFeeBasisPoints = 100 //~1%;
 
+  const maxRoyaltyFeeBasisPoints = 1000 //~10%;
+
+  const vendorRoyaltyBasisPoints = 300 //~3%;
+
+  const protocolFeeBasisPoints = 50 //~0.5%;
+
   const price = new anchor.BN(0.1 * LAMPORTS_PER_SOL) // 0.1 SOL;
 
   const askPrice = new anchor.BN(0.5 * LAMPORTS_PER_SOL) //0.5 SOl
@@ -34,9 +43,14 @@ describe("two-sided-services-marketplace", () => {
     program.programId
   )[0];
 
+  const treasuryAccount = PublicKey.findProgramAddressSync(
+    [Buffer.from("treasury")],
+    program.programId
+  )[0];
+
   it("Is initialized!", async () => {
     admin = await createUserWithLamports(connection, 10);
-    await program.methods.initialize(royaltyFeeBasisPoints).accountsPartial({
+    await program.methods.initialize(royaltyFeeBasisPoints, maxRoyaltyFeeBasisPoints, protocolFeeBasisPoints).accountsPartial({
       admin: admin.publicKey
     }).signers([admin]).rpc();
 
@@ -44,6 +58,91 @@ describe("two-sided-services-marketplace", () => {
     expect(configData.isInitialized).to.equal(true);
     expect(configData.admin.toString()).to.equal(admin.publicKey.toString());
     expect(configData.royaltyFeeBasisPoints).to.equal(royaltyFeeBasisPoints);
+    expect(configData.maxRoyaltyFeeBasisPoints).to.equal(maxRoyaltyFeeBasisPoints);
+    expect(configData.protocolFeeBasisPoints).to.equal(protocolFeeBasisPoints);
+  });
+
+  it("Update royalty", async () => {
+    const newRoyaltyFeeBasisPoints = 200 //~2%;
+    await program.methods.updateRoyalty(newRoyaltyFeeBasisPoints).accountsPartial({
+      admin: admin.publicKey,
+      configAccount: configAccount,
+    }).signers([admin]).rpc();
+
+    let configData = await program.account.configAccount.fetch(configAccount);
+    expect(configData.royaltyFeeBasisPoints).to.equal(newRoyaltyFeeBasisPoints);
+
+    try {
+      await program.methods.updateRoyalty(maxRoyaltyFeeBasisPoints + 1).accountsPartial({
+        admin: admin.publicKey,
+        configAccount: configAccount,
+      }).signers([admin]).rpc();
+      expect.fail("royalty above the maximum should be rejected");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("RoyaltyExceedsMaximum");
+    }
+
+    await program.methods.updateRoyalty(royaltyFeeBasisPoints).accountsPartial({
+      admin: admin.publicKey,
+      configAccount: configAccount,
+    }).signers([admin]).rpc();
+
+    configData = await program.account.configAccount.fetch(configAccount);
+    expect(configData.royaltyFeeBasisPoints).to.equal(royaltyFeeBasisPoints);
+  });
+
+  it("Hand over admin", async () => {
+    const newAdmin = await createUserWithLamports(connection, 10);
+
+    await program.methods.proposeAdmin(newAdmin.publicKey).accountsPartial({
+      admin: admin.publicKey,
+      configAccount: configAccount,
+    }).signers([admin]).rpc();
+
+    await program.methods.cancelAdminProposal().accountsPartial({
+      admin: admin.publicKey,
+      configAccount: configAccount,
+    }).signers([admin]).rpc();
+
+    let configData = await program.account.configAccount.fetch(configAccount);
+    expect(configData.pendingAdmin).to.equal(null);
+
+    await program.methods.proposeAdmin(newAdmin.publicKey).accountsPartial({
+      admin: admin.publicKey,
+      configAccount: configAccount,
+    }).signers([admin]).rpc();
+
+    await program.methods.acceptAdmin().accountsPartial({
+      newAdmin: newAdmin.publicKey,
+      configAccount: configAccount,
+    }).signers([newAdmin]).rpc();
+
+    configData = await program.account.configAccount.fetch(configAccount);
+    expect(configData.admin.toString()).to.equal(newAdmin.publicKey.toString());
+    expect(configData.pendingAdmin).to.equal(null);
+
+    admin = newAdmin;
+  });
+
+  it("Update default URI template", async () => {
+    const { defaultUriTemplate } = await program.account.configAccount.fetch(configAccount);
+
+    // Short enough as written, but too long once the placeholder becomes a mint address
+    const template = "https://example.com/" + "a".repeat(170) + "/{mint}";
+    try {
+      await program.methods.updateDefaultUriTemplate(template).accountsPartial({
+        admin: admin.publicKey,
+        configAccount: configAccount,
+      }).signers([admin]).rpc();
+      expect.fail("template expanding past the maximum URI length should be rejected");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("UriTooLong");
+    }
+
+    await program.methods.updateDefaultUriTemplate(defaultUriTemplate).accountsPartial({
+      admin: admin.publicKey,
+      configAccount: configAccount,
+    }).signers([admin]).rpc();
   });
 
   it("Create services", async () => {
@@ -57,6 +156,7 @@ describe("two-sided-services-marketplace", () => {
     )[0];
 
     vendor = await createUserWithLamports(connection, 10);
+    teammate = await createUserWithLamports(connection, 1);
 
     configTokenAccount = getAssociatedTokenAddressSync(
       nftMint.publicKey,
@@ -69,11 +169,18 @@ describe("two-sided-services-marketplace", () => {
     const isSouldBound = false;
 
     const agreements = [
-      { title: "agreement1", details: "details1" },
-      { title: "agreement2", details: "details2" },
+      { title: "agreement1", details: "details1", amount: new anchor.BN(0.04 * LAMPORTS_PER_SOL) },
+      { title: "agreement2", details: "details2", amount: new anchor.BN(0.06 * LAMPORTS_PER_SOL) },
+    ];
+
+    const creators = [
+      { address: vendor.publicKey, share: 70 },
+      { address: teammate.publicKey, share: 30 },
     ];
 
-    await program.methods.createService(isSouldBound, agreements, price).accountsPartial({
+    const metadata = { name: "Logo design", symbol: "LOGO", uri: "https://example.com/logo.json" };
+
+    await program.methods.createService(isSouldBound, agreements, price, vendorRoyaltyBasisPoints, creators, metadata).accountsPartial({
       vendor: vendor.publicKey,
       configAccount: configAccount,
       nftMint: nftMint.publicKey,
@@ -89,10 +196,204 @@ describe("two-sided-services-marketplace", () => {
     expect(serviceData.originalVendor.toString()).to.equal(vendor.publicKey.toString());
     expect(serviceData.currentVendor.toString()).to.equal(vendor.publicKey.toString());
     expect(serviceData.price.toString()).to.equal(price.toString());
+    expect(serviceData.royaltyBasisPoints).to.equal(vendorRoyaltyBasisPoints);
+    expect(serviceData.creators.map((creator) => creator.share)).to.deep.equal([70, 30]);
+    expect(serviceData.milestoneAmounts.map((amount) => amount.toString())).to.deep.equal(agreements.map((agreement) => agreement.amount.toString()));
+
+    const tokenMetadata = await getTokenMetadata(connection, nftMint.publicKey, 'confirmed', TOKEN_2022_PROGRAM_ID);
+    expect(tokenMetadata.name).to.equal(metadata.name);
+    expect(tokenMetadata.symbol).to.equal(metadata.symbol);
+    expect(tokenMetadata.uri).to.equal(metadata.uri);
+
+    // The mint holds exactly the rent of its final size
+    const mintInfo = await connection.getAccountInfo(nftMint.publicKey);
+    expect(mintInfo.lamports).to.equal(await connection.getMinimumBalanceForRentExemption(mintInfo.data.length));
+
+    const rejectedMint = Keypair.generate();
+    try {
+      await program.methods.createService(false, [{ title: "t".repeat(33), details: "details", amount: price }], price, null, [], metadata).accountsPartial({
+        vendor: vendor.publicKey,
+        configAccount: configAccount,
+        nftMint: rejectedMint.publicKey,
+        serviceAccount: PublicKey.findProgramAddressSync([Buffer.from("service"), rejectedMint.publicKey.toBuffer()], program.programId)[0],
+        configTokenAccount: getAssociatedTokenAddressSync(rejectedMint.publicKey, configAccount, true, TOKEN_2022_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID),
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      }).signers([vendor, rejectedMint]).rpc();
+      expect.fail("agreement title above the limit should be rejected");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("InvalidAgreementTitle");
+    }
+  })
+
+  it("Vendor profile", async () => {
+    const vendorProfile = PublicKey.findProgramAddressSync(
+      [Buffer.from("vendor_profile"), vendor.publicKey.toBuffer()],
+      program.programId
+    )[0];
+
+    await program.methods.updateVendorProfile("Vendor", "https://vendor.example").accountsPartial({
+      vendor: vendor.publicKey,
+      vendorProfile: vendorProfile,
+    }).signers([vendor]).rpc();
+
+    await program.methods.setVendorVerified(true).accountsPartial({
+      admin: admin.publicKey,
+      configAccount: configAccount,
+      vendorProfile: vendorProfile,
+    }).signers([admin]).rpc();
+
+    const profileData = await program.account.vendorProfileAccount.fetch(vendorProfile);
+    expect(profileData.displayName).to.equal("Vendor");
+    expect(profileData.contactUri).to.equal("https://vendor.example");
+    expect(profileData.isVerified).to.equal(true);
+    expect(profileData.serviceCount).to.equal(1);
+  })
+
+  it("Update and remove agreements", async () => {
+    const longDetails = "details1 ".repeat(40);
+
+    await program.methods.updateAgreement("agreement1", longDetails).accountsPartial({
+      vendor: vendor.publicKey,
+      configAccount: configAccount,
+      serviceAccount: serviceAccount,
+      nftMint: nftMint.publicKey,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    }).signers([vendor]).rpc();
+
+    await program.methods.updateAgreement("agreement3", "details3").accountsPartial({
+      vendor: vendor.publicKey,
+      configAccount: configAccount,
+      serviceAccount: serviceAccount,
+      nftMint: nftMint.publicKey,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    }).signers([vendor]).rpc();
+
+    let tokenMetadata = await getTokenMetadata(connection, nftMint.publicKey, 'confirmed', TOKEN_2022_PROGRAM_ID);
+    expect(tokenMetadata.additionalMetadata).to.deep.equal([
+      ["agreement1", longDetails],
+      ["agreement2", "details2"],
+      ["agreement3", "details3"],
+    ]);
+
+    // Agreements added after creation carry no milestone
+    const milestoneCount = (await program.account.serviceAccount.fetch(serviceAccount)).milestoneAmounts.length;
+    expect(milestoneCount).to.equal(2);
+
+    // The rent freed by the smaller metadata goes back to the vendor
+    const vendorBalanceBefore = await connection.getBalance(vendor.publicKey);
+    await program.methods.removeAgreement("agreement3").accountsPartial({
+      vendor: vendor.publicKey,
+      configAccount: configAccount,
+      serviceAccount: serviceAccount,
+      nftMint: nftMint.publicKey,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    }).signers([vendor]).rpc();
+    expect(await connection.getBalance(vendor.publicKey)).to.be.greaterThan(vendorBalanceBefore);
+
+    tokenMetadata = await getTokenMetadata(connection, nftMint.publicKey, 'confirmed', TOKEN_2022_PROGRAM_ID);
+    expect(tokenMetadata.additionalMetadata.map(([key]) => key)).to.deep.equal(["agreement1", "agreement2"]);
+
+    const serviceData = await program.account.serviceAccount.fetch(serviceAccount);
+    expect(serviceData.milestoneAmounts.map((amount) => amount.toString())).to.deep.equal([
+      new anchor.BN(0.04 * LAMPORTS_PER_SOL).toString(),
+      new anchor.BN(0.06 * LAMPORTS_PER_SOL).toString(),
+    ]);
+
+    try {
+      await program.methods.removeAgreement("agreement3").accountsPartial({
+        vendor: vendor.publicKey,
+        configAccount: configAccount,
+        serviceAccount: serviceAccount,
+        nftMint: nftMint.publicKey,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      }).signers([vendor]).rpc();
+      expect.fail("removing a missing agreement should be rejected");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("AgreementNotFound");
+    }
+
+    // A service paid in milestones keeps at least one agreement to carry the price
+    const singleVendor = await createUserWithLamports(connection, 10);
+    const singleNftMint = Keypair.generate();
+    const singleServiceAccount = PublicKey.findProgramAddressSync(
+      [Buffer.from("service"), singleNftMint.publicKey.toBuffer()],
+      program.programId
+    )[0];
+
+    await program.methods.createService(false, [{ title: "agreement1", details: "details1", amount: price }], price, null, [], { name: "Single milestone", symbol: "ONE", uri: null }).accountsPartial({
+      vendor: singleVendor.publicKey,
+      configAccount: configAccount,
+      nftMint: singleNftMint.publicKey,
+      serviceAccount: singleServiceAccount,
+      configTokenAccount: getAssociatedTokenAddressSync(singleNftMint.publicKey, configAccount, true, TOKEN_2022_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID),
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    }).signers([singleVendor, singleNftMint]).rpc();
+
+    try {
+      await program.methods.removeAgreement("agreement1").accountsPartial({
+        vendor: singleVendor.publicKey,
+        configAccount: configAccount,
+        serviceAccount: singleServiceAccount,
+        nftMint: singleNftMint.publicKey,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      }).signers([singleVendor]).rpc();
+      expect.fail("removing the last milestone agreement should be rejected");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("LastMilestoneAgreement");
+    }
+  })
+
+  it("Pause marketplace", async () => {
+    await program.methods.setPaused(true).accountsPartial({
+      admin: admin.publicKey,
+      configAccount: configAccount,
+    }).signers([admin]).rpc();
+
+    try {
+      await program.methods.buyService(price).accountsPartial({
+        buyer: buyer.publicKey,
+        configAccount: configAccount,
+        serviceAccount: serviceAccount,
+        currentVendor: vendor.publicKey,
+        originalVendor: vendor.publicKey,
+      }).signers([buyer]).rpc();
+      expect.fail("buying while paused should be rejected");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("MarketplacePaused");
+    }
+
+    await program.methods.setPaused(false).accountsPartial({
+      admin: admin.publicKey,
+      configAccount: configAccount,
+    }).signers([admin]).rpc();
+  })
+
+  it("Delist and relist service", async () => {
+    await program.methods.setServiceListed(false).accountsPartial({
+      vendor: vendor.publicKey,
+      serviceAccount: serviceAccount,
+    }).signers([vendor]).rpc();
+
+    let serviceData = await program.account.serviceAccount.fetch(serviceAccount);
+    expect(serviceData.isListed).to.equal(false);
+
+    await program.methods.setServiceListed(true).accountsPartial({
+      vendor: vendor.publicKey,
+      serviceAccount: serviceAccount,
+    }).signers([vendor]).rpc();
+
+    serviceData = await program.account.serviceAccount.fetch(serviceAccount);
+    expect(serviceData.isListed).to.equal(true);
   })
 
   it("Buy service", async () => {
-    await program.methods.buyService().accountsPartial({
+    const treasuryBalanceBefore = await connection.getBalance(treasuryAccount);
+
+    await program.methods.buyService(price).accountsPartial({
       buyer: buyer.publicKey,
       configAccount: configAccount,
       serviceAccount: serviceAccount,
@@ -103,6 +404,63 @@ describe("two-sided-services-marketplace", () => {
     const serviceData = await program.account.serviceAccount.fetch(serviceAccount);
 
     expect(serviceData.currentVendor.toString()).to.equal(buyer.publicKey.toString());
+
+    const treasuryBalanceAfter = await connection.getBalance(treasuryAccount);
+    const protocolFee = price.muln(protocolFeeBasisPoints).divn(10_000);
+    expect(treasuryBalanceAfter - treasuryBalanceBefore).to.equal(protocolFee.toNumber());
+  })
+
+  it("Review purchase", async () => {
+    const receiptAccount = PublicKey.findProgramAddressSync(
+      [Buffer.from("receipt"), nftMint.publicKey.toBuffer(), buyer.publicKey.toBuffer()],
+      program.programId
+    )[0];
+    const vendorProfile = PublicKey.findProgramAddressSync(
+      [Buffer.from("vendor_profile"), vendor.publicKey.toBuffer()],
+      program.programId
+    )[0];
+    const commentHash = Array.from(Buffer.alloc(32, 7));
+
+    try {
+      await program.methods.submitReview(6, commentHash).accountsPartial({
+        buyer: buyer.publicKey,
+        receiptAccount: receiptAccount,
+        serviceAccount: serviceAccount,
+        vendorProfile: vendorProfile,
+      }).signers([buyer]).rpc();
+      expect.fail("score above 5 should be rejected");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("InvalidReviewScore");
+    }
+
+    await program.methods.submitReview(4, commentHash).accountsPartial({
+      buyer: buyer.publicKey,
+      receiptAccount: receiptAccount,
+      serviceAccount: serviceAccount,
+      vendorProfile: vendorProfile,
+    }).signers([buyer]).rpc();
+
+    const serviceData = await program.account.serviceAccount.fetch(serviceAccount);
+    expect(serviceData.rating.count).to.equal(1);
+    expect(serviceData.rating.average).to.equal(400);
+
+    const profileData = await program.account.vendorProfileAccount.fetch(vendorProfile);
+    expect(profileData.rating.count).to.equal(1);
+    expect(profileData.salesCount.toNumber()).to.equal(1);
+    expect(profileData.salesVolume.toString()).to.equal(price.toString());
+
+    // Each purchase can only be reviewed once
+    try {
+      await program.methods.submitReview(5, commentHash).accountsPartial({
+        buyer: buyer.publicKey,
+        receiptAccount: receiptAccount,
+        serviceAccount: serviceAccount,
+        vendorProfile: vendorProfile,
+      }).signers([buyer]).rpc();
+      expect.fail("second review should be rejected");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("PurchaseAlreadyReviewed");
+    }
   })
 
   it("Update new service price", async () => {
@@ -115,21 +473,27 @@ describe("two-sided-services-marketplace", () => {
     const serviceData = await program.account.serviceAccount.fetch(serviceAccount);
 
     expect(serviceData.price.toString()).to.equal(newServicePrice.toString());
+    // Milestones are rescaled to the new price
+    expect(serviceData.milestoneAmounts.map((amount) => amount.toString())).to.deep.equal([
+      new anchor.BN(0.28 * LAMPORTS_PER_SOL).toString(),
+      new anchor.BN(0.42 * LAMPORTS_PER_SOL).toString(),
+    ]);
   })
 
   it("Ask service", async () => {
     asker = await createUserWithLamports(connection, 10);
 
     askAccount = PublicKey.findProgramAddressSync(
-      [Buffer.from("ask"), nftMint.publicKey.toBuffer()],
+      [Buffer.from("ask"), nftMint.publicKey.toBuffer(), asker.publicKey.toBuffer()],
       program.programId
     )[0];
 
-    await program.methods.askService(askPrice).accountsPartial({
+    await program.methods.askService(askPrice, null).accountsPartial({
       asker: asker.publicKey,
       configAccount: configAccount,
       askAccount: askAccount,
-      nftMint: nftMint.publicKey
+      nftMint: nftMint.publicKey,
+      serviceAccount: serviceAccount,
     }).signers([asker]).rpc();
 
     const askData = await program.account.askAccount.fetch(askAccount);
@@ -143,6 +507,7 @@ describe("two-sided-services-marketplace", () => {
     await program.methods.updateAskPrice(newAskPrice).accountsPartial({
       asker: asker.publicKey,
       askAccount: askAccount,
+      serviceAccount: serviceAccount,
     }).signers([asker]).rpc();
 
     const askData = await program.account.askAccount.fetch(askAccount);
@@ -150,21 +515,213 @@ describe("two-sided-services-marketplace", () => {
     expect(askData.askPrice.toString()).to.equal(newAskPrice.toString());
   })
 
+  it("Cancel ask", async () => {
+    await program.methods.cancelAsk().accountsPartial({
+      asker: asker.publicKey,
+      askAccount: askAccount,
+      serviceAccount: serviceAccount,
+    }).signers([asker]).rpc();
+
+    expect(await program.account.askAccount.fetchNullable(askAccount)).to.equal(null);
+
+    await program.methods.askService(askPrice, null).accountsPartial({
+      asker: asker.publicKey,
+      configAccount: configAccount,
+      askAccount: askAccount,
+      nftMint: nftMint.publicKey,
+      serviceAccount: serviceAccount,
+    }).signers([asker]).rpc();
+  })
+
+  it("Ask service from another asker", async () => {
+    otherAsker = await createUserWithLamports(connection, 10);
+
+    otherAskAccount = PublicKey.findProgramAddressSync(
+      [Buffer.from("ask"), nftMint.publicKey.toBuffer(), otherAsker.publicKey.toBuffer()],
+      program.programId
+    )[0];
+
+    await program.methods.askService(askPrice, null).accountsPartial({
+      asker: otherAsker.publicKey,
+      configAccount: configAccount,
+      askAccount: otherAskAccount,
+      nftMint: nftMint.publicKey,
+      serviceAccount: serviceAccount,
+    }).signers([otherAsker]).rpc();
+
+    // Both bids stay open side by side
+    const askData = await program.account.askAccount.fetch(askAccount);
+    const otherAskData = await program.account.askAccount.fetch(otherAskAccount);
+    expect(askData.asker.toString()).to.equal(asker.publicKey.toString());
+    expect(otherAskData.asker.toString()).to.equal(otherAsker.publicKey.toString());
+  })
+
   it("Accpect ask", async () => {
-    await program.methods.acceptAsk().accountsPartial({
+    const teammateBalanceBefore = await connection.getBalance(teammate.publicKey);
+
+    // The vendor refuses to sell below the price they saw
+    try {
+      await program.methods.acceptAsk(askPrice.addn(1)).accountsPartial({
+        vendor: buyer.publicKey,
+        asker: asker.publicKey,
+        configAccount: configAccount,
+        askAccount: askAccount,
+        serviceAccount: serviceAccount,
+        originalVendor: vendor.publicKey
+      }).remainingAccounts([
+        { pubkey: vendor.publicKey, isWritable: true, isSigner: false },
+        { pubkey: teammate.publicKey, isWritable: true, isSigner: false },
+      ]).signers([buyer]).rpc();
+      expect.fail("ask below the vendor's minimum should be rejected");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("AskPriceBelowMinimum");
+    }
+
+    await program.methods.acceptAsk(askPrice).accountsPartial({
       vendor: buyer.publicKey,
       asker: asker.publicKey,
       configAccount: configAccount,
       askAccount: askAccount,
       serviceAccount: serviceAccount,
       originalVendor: vendor.publicKey
-    }).signers([buyer]).rpc();
+    }).remainingAccounts([
+      { pubkey: vendor.publicKey, isWritable: true, isSigner: false },
+      { pubkey: teammate.publicKey, isWritable: true, isSigner: false },
+    ]).signers([buyer]).rpc();
 
     const serviceData = await program.account.serviceAccount.fetch(serviceAccount);
 
     expect(serviceData.currentVendor.toString()).to.equal(asker.publicKey.toString())
+    expect(await program.account.askAccount.fetchNullable(askAccount)).to.equal(null);
+
+    const teammateBalanceAfter = await connection.getBalance(teammate.publicKey);
+    const teammateRoyalty = askPrice.muln(vendorRoyaltyBasisPoints).divn(10_000).muln(30).divn(100);
+    expect(teammateBalanceAfter - teammateBalanceBefore).to.equal(teammateRoyalty.toNumber());
+  });
+
+  it("Cancel losing ask", async () => {
+    await program.methods.cancelAsk().accountsPartial({
+      asker: otherAsker.publicKey,
+      askAccount: otherAskAccount,
+      serviceAccount: serviceAccount,
+    }).signers([otherAsker]).rpc();
+
+    expect(await program.account.askAccount.fetchNullable(otherAskAccount)).to.equal(null);
   });
 
+  it("Withdraw treasury", async () => {
+    const recipient = await createUserWithLamports(connection, 1);
+    const amount = price.muln(protocolFeeBasisPoints).divn(10_000);
+    const recipientBalanceBefore = await connection.getBalance(recipient.publicKey);
+
+    await program.methods.withdrawTreasury(amount).accountsPartial({
+      admin: admin.publicKey,
+      recipient: recipient.publicKey,
+      configAccount: configAccount,
+    }).signers([admin]).rpc();
+
+    const recipientBalanceAfter = await connection.getBalance(recipient.publicKey);
+    expect(recipientBalanceAfter - recipientBalanceBefore).to.equal(amount.toNumber());
+  });
+
+  it("Reject payment mint with a transfer fee", async () => {
+    const feeMint = Keypair.generate();
+    const mintLen = getMintLen([ExtensionType.TransferFeeConfig]);
+
+    await anchor.web3.sendAndConfirmTransaction(connection, new anchor.web3.Transaction().add(
+      anchor.web3.SystemProgram.createAccount({
+        fromPubkey: admin.publicKey,
+        newAccountPubkey: feeMint.publicKey,
+        space: mintLen,
+        lamports: await connection.getMinimumBalanceForRentExemption(mintLen),
+        programId: TOKEN_2022_PROGRAM_ID,
+      }),
+      createInitializeTransferFeeConfigInstruction(feeMint.publicKey, admin.publicKey, admin.publicKey, 100, BigInt(1_000_000), TOKEN_2022_PROGRAM_ID),
+      createInitializeMintInstruction(feeMint.publicKey, 6, admin.publicKey, null, TOKEN_2022_PROGRAM_ID),
+    ), [admin, feeMint]);
+
+    try {
+      await program.methods.setPaymentMint(true).accountsPartial({
+        admin: admin.publicKey,
+        configAccount: configAccount,
+        paymentMint: feeMint.publicKey,
+      }).signers([admin]).rpc();
+      expect.fail("payment mint with a transfer fee should be rejected");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("UnsupportedPaymentMint");
+    }
+  });
+
+  it("Buy service paid in SPL token", async () => {
+    const tokenVendor = await createUserWithLamports(connection, 10);
+    const tokenBuyer = await createUserWithLamports(connection, 10);
+    const tokenNftMint = Keypair.generate();
+    const tokenPrice = new anchor.BN(1_000_000); // 1 USDC
+
+    const paymentMint = await createMint(connection, tokenVendor, tokenVendor.publicKey, null, 6);
+    const vendorPaymentAccount = await createAssociatedTokenAccount(connection, tokenVendor, paymentMint, tokenVendor.publicKey);
+    const buyerPaymentAccount = await createAssociatedTokenAccount(connection, tokenBuyer, paymentMint, tokenBuyer.publicKey);
+    await mintTo(connection, tokenVendor, paymentMint, buyerPaymentAccount, tokenVendor, tokenPrice.toNumber());
+
+    await program.methods.setPaymentMint(true).accountsPartial({
+      admin: admin.publicKey,
+      configAccount: configAccount,
+      paymentMint: paymentMint,
+    }).signers([admin]).rpc();
+
+    const tokenServiceAccount = PublicKey.findProgramAddressSync(
+      [Buffer.from("service"), tokenNftMint.publicKey.toBuffer()],
+      program.programId
+    )[0];
+
+    await program.methods.createService(false, [], tokenPrice, null, [], { name: "Token service", symbol: "TKN", uri: null }).accountsPartial({
+      vendor: tokenVendor.publicKey,
+      configAccount: configAccount,
+      nftMint: tokenNftMint.publicKey,
+      serviceAccount: tokenServiceAccount,
+      configTokenAccount: getAssociatedTokenAddressSync(tokenNftMint.publicKey, configAccount, true, TOKEN_2022_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID),
+      paymentMint: paymentMint,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    }).signers([tokenVendor, tokenNftMint]).rpc();
+
+    // Without a URI the default template of the config is used
+    const tokenMetadata = await getTokenMetadata(connection, tokenNftMint.publicKey, 'confirmed', TOKEN_2022_PROGRAM_ID);
+    expect(tokenMetadata.uri).to.equal((await program.account.configAccount.fetch(configAccount)).defaultUriTemplate);
+
+    await program.methods.buyService(tokenPrice).accountsPartial({
+      buyer: tokenBuyer.publicKey,
+      configAccount: configAccount,
+      serviceAccount: tokenServiceAccount,
+      currentVendor: tokenVendor.publicKey,
+      originalVendor: tokenVendor.publicKey,
+      paymentMint: paymentMint,
+      buyerTokenAccount: buyerPaymentAccount,
+      currentVendorTokenAccount: vendorPaymentAccount,
+      treasuryTokenAccount: getAssociatedTokenAddressSync(paymentMint, treasuryAccount, true),
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+    }).signers([tokenBuyer]).rpc();
+
+    const protocolFee = tokenPrice.muln(protocolFeeBasisPoints).divn(10_000);
+    const vendorPayment = await getAccount(connection, vendorPaymentAccount);
+    expect(vendorPayment.amount.toString()).to.equal(tokenPrice.sub(protocolFee).toString());
+
+    const serviceData = await program.account.serviceAccount.fetch(tokenServiceAccount);
+    expect(serviceData.currentVendor.toString()).to.equal(tokenBuyer.publicKey.toString());
+    expect(serviceData.paymentMint.toString()).to.equal(paymentMint.toString());
+
+    // Token sales are counted but not added to the lamport volume
+    const profileData = await program.account.vendorProfileAccount.fetch(PublicKey.findProgramAddressSync(
+      [Buffer.from("vendor_profile"), tokenVendor.publicKey.toBuffer()],
+      program.programId
+    )[0]);
+    expect(profileData.salesCount.toNumber()).to.equal(1);
+    expect(profileData.tokenSalesCount.toNumber()).to.equal(1);
+    expect(profileData.salesVolume.toNumber()).to.equal(0);
+  })
+
   it("Withdraw service", async () => {
 
     const vendorTokenAccount = getAssociatedTokenAddressSync(
@@ -186,6 +743,716 @@ describe("two-sided-services-marketplace", () => {
       associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
       systemProgram: anchor.web3.SystemProgram.programId,
     }).signers([asker]).rpc();
+
+    const serviceData = await program.account.serviceAccount.fetch(serviceAccount);
+    expect(serviceData.isInCustody).to.equal(false);
+  })
+
+  it("Deposit service", async () => {
+    const depositorTokenAccount = getAssociatedTokenAddressSync(
+      nftMint.publicKey,
+      asker.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID,
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    );
+
+    await program.methods.depositService().accountsPartial({
+      depositor: asker.publicKey,
+      configAccount: configAccount,
+      configTokenAccount: configTokenAccount,
+      nftMint: nftMint.publicKey,
+      depositorTokenAccount: depositorTokenAccount,
+      serviceAccount: serviceAccount,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    }).signers([asker]).rpc();
+
+    const serviceData = await program.account.serviceAccount.fetch(serviceAccount);
+    expect(serviceData.isInCustody).to.equal(true);
+    expect(serviceData.currentVendor.toString()).to.equal(asker.publicKey.toString());
+  })
+
+  it("Purchase order and confirm delivery", async () => {
+    const orderAccount = PublicKey.findProgramAddressSync(
+      [Buffer.from("order"), nftMint.publicKey.toBuffer(), buyer.publicKey.toBuffer()],
+      program.programId
+    )[0];
+
+    const { price: orderPrice } = await program.account.serviceAccount.fetch(serviceAccount);
+
+    // The vendor cannot open an order on their own service
+    try {
+      await program.methods.purchaseOrder(orderPrice).accountsPartial({
+        buyer: asker.publicKey,
+        configAccount: configAccount,
+        serviceAccount: serviceAccount,
+        orderAccount: PublicKey.findProgramAddressSync(
+          [Buffer.from("order"), nftMint.publicKey.toBuffer(), asker.publicKey.toBuffer()],
+          program.programId
+        )[0],
+      }).signers([asker]).rpc();
+      expect.fail("vendor ordering their own service should be rejected");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("CannotBuyOwnService");
+    }
+
+    // The buyer caps the price in case the vendor raises it first
+    try {
+      await program.methods.purchaseOrder(orderPrice.subn(1)).accountsPartial({
+        buyer: buyer.publicKey,
+        configAccount: configAccount,
+        serviceAccount: serviceAccount,
+        orderAccount: orderAccount,
+      }).signers([buyer]).rpc();
+      expect.fail("price above the buyer's maximum should be rejected");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("PriceAboveMaximum");
+    }
+
+    await program.methods.purchaseOrder(orderPrice).accountsPartial({
+      buyer: buyer.publicKey,
+      configAccount: configAccount,
+      serviceAccount: serviceAccount,
+      orderAccount: orderAccount,
+    }).signers([buyer]).rpc();
+
+    let serviceData = await program.account.serviceAccount.fetch(serviceAccount);
+    expect(serviceData.openOrders).to.equal(1);
+
+    const orderData = await program.account.orderAccount.fetch(orderAccount);
+    expect(orderData.vendor.toString()).to.equal(asker.publicKey.toString());
+    expect(orderData.amount.toString()).to.equal(serviceData.price.toString());
+
+    const receiptAccount = PublicKey.findProgramAddressSync(
+      [Buffer.from("receipt"), nftMint.publicKey.toBuffer(), buyer.publicKey.toBuffer()],
+      program.programId
+    )[0];
+    const vendorProfile = PublicKey.findProgramAddressSync(
+      [Buffer.from("vendor_profile"), asker.publicKey.toBuffer()],
+      program.programId
+    )[0];
+    const salesCountBefore = (await program.account.vendorProfileAccount.fetchNullable(vendorProfile))?.salesCount.toNumber() ?? 0;
+
+    await program.methods.confirmDelivery().accountsPartial({
+      buyer: buyer.publicKey,
+      vendor: asker.publicKey,
+      configAccount: configAccount,
+      orderAccount: orderAccount,
+      serviceAccount: serviceAccount,
+      receiptAccount: receiptAccount,
+    }).remainingAccounts([
+      { pubkey: vendor.publicKey, isWritable: true, isSigner: false },
+      { pubkey: teammate.publicKey, isWritable: true, isSigner: false },
+    ]).signers([buyer]).rpc();
+
+    serviceData = await program.account.serviceAccount.fetch(serviceAccount);
+    expect(serviceData.openOrders).to.equal(0);
+    expect(serviceData.currentVendor.toString()).to.equal(buyer.publicKey.toString());
+    expect(await program.account.orderAccount.fetchNullable(orderAccount)).to.equal(null);
+
+    const receiptData = await program.account.purchaseReceiptAccount.fetch(receiptAccount);
+    expect(receiptData.vendor.toString()).to.equal(asker.publicKey.toString());
+    expect(receiptData.price.toString()).to.equal(orderData.amount.toString());
+
+    const profileData = await program.account.vendorProfileAccount.fetch(vendorProfile);
+    expect(profileData.salesCount.toNumber()).to.equal(salesCountBefore + 1);
+  })
+
+  it("Refund order", async () => {
+    const orderAccount = PublicKey.findProgramAddressSync(
+      [Buffer.from("order"), nftMint.publicKey.toBuffer(), otherAsker.publicKey.toBuffer()],
+      program.programId
+    )[0];
+
+    await program.methods.purchaseOrder((await program.account.serviceAccount.fetch(serviceAccount)).price).accountsPartial({
+      buyer: otherAsker.publicKey,
+      configAccount: configAccount,
+      serviceAccount: serviceAccount,
+      orderAccount: orderAccount,
+    }).signers([otherAsker]).rpc();
+
+    // The buyer has to wait for the refund timeout
+    try {
+      await program.methods.refundOrder().accountsPartial({
+        authority: otherAsker.publicKey,
+        buyer: otherAsker.publicKey,
+        orderAccount: orderAccount,
+        serviceAccount: serviceAccount,
+      }).signers([otherAsker]).rpc();
+      expect.fail("refund before the timeout should be rejected");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("OrderNotRefundable");
+    }
+
+    // The vendor can refund right away
+    await program.methods.refundOrder().accountsPartial({
+      authority: buyer.publicKey,
+      buyer: otherAsker.publicKey,
+      orderAccount: orderAccount,
+      serviceAccount: serviceAccount,
+    }).signers([buyer]).rpc();
+
+    const serviceData = await program.account.serviceAccount.fetch(serviceAccount);
+    expect(serviceData.openOrders).to.equal(0);
+    expect(await program.account.orderAccount.fetchNullable(orderAccount)).to.equal(null);
+  })
+
+  it("Release milestones", async () => {
+    const orderAccount = PublicKey.findProgramAddressSync(
+      [Buffer.from("order"), nftMint.publicKey.toBuffer(), otherAsker.publicKey.toBuffer()],
+      program.programId
+    )[0];
+
+    await program.methods.purchaseOrder((await program.account.serviceAccount.fetch(serviceAccount)).price).accountsPartial({
+      buyer: otherAsker.publicKey,
+      configAccount: configAccount,
+      serviceAccount: serviceAccount,
+      orderAccount: orderAccount,
+    }).signers([otherAsker]).rpc();
+
+    const receiptAccount = PublicKey.findProgramAddressSync(
+      [Buffer.from("receipt"), nftMint.publicKey.toBuffer(), otherAsker.publicKey.toBuffer()],
+      program.programId
+    )[0];
+
+    for (let milestone = 0; milestone < 2; milestone++) {
+      await program.methods.requestMilestoneRelease().accountsPartial({
+        vendor: buyer.publicKey,
+        orderAccount: orderAccount,
+      }).signers([buyer]).rpc();
+
+      await program.methods.approveMilestoneRelease().accountsPartial({
+        buyer: otherAsker.publicKey,
+        vendor: buyer.publicKey,
+        configAccount: configAccount,
+        orderAccount: orderAccount,
+        serviceAccount: serviceAccount,
+        // Only the last release completes the order and writes the receipt
+        receiptAccount: milestone == 1 ? receiptAccount : null,
+      }).remainingAccounts([
+        { pubkey: vendor.publicKey, isWritable: true, isSigner: false },
+        { pubkey: teammate.publicKey, isWritable: true, isSigner: false },
+      ]).signers([otherAsker]).rpc();
+    }
+
+    const receiptData = await program.account.purchaseReceiptAccount.fetch(receiptAccount);
+    expect(receiptData.buyer.toString()).to.equal(otherAsker.publicKey.toString());
+    expect(receiptData.vendor.toString()).to.equal(buyer.publicKey.toString());
+
+    const serviceData = await program.account.serviceAccount.fetch(serviceAccount);
+    expect(serviceData.openOrders).to.equal(0);
+    expect(serviceData.currentVendor.toString()).to.equal(otherAsker.publicKey.toString());
+    expect(await program.account.orderAccount.fetchNullable(orderAccount)).to.equal(null);
+  })
+
+  it("Resolve dispute", async () => {
+    const arbiter = await createUserWithLamports(connection, 1);
+    const orderAccount = PublicKey.findProgramAddressSync(
+      [Buffer.from("order"), nftMint.publicKey.toBuffer(), buyer.publicKey.toBuffer()],
+      program.programId
+    )[0];
+
+    await program.methods.setArbiter(arbiter.publicKey, true).accountsPartial({
+      admin: admin.publicKey,
+      configAccount: configAccount,
+    }).signers([admin]).rpc();
+
+    await program.methods.purchaseOrder((await program.account.serviceAccount.fetch(serviceAccount)).price).accountsPartial({
+      buyer: buyer.publicKey,
+      configAccount: configAccount,
+      serviceAccount: serviceAccount,
+      orderAccount: orderAccount,
+    }).signers([buyer]).rpc();
+
+    await program.methods.openDispute().accountsPartial({
+      authority: otherAsker.publicKey,
+      orderAccount: orderAccount,
+    }).signers([otherAsker]).rpc();
+
+    // The buyer can no longer take the timeout refund
+    try {
+      await program.methods.refundOrder().accountsPartial({
+        authority: buyer.publicKey,
+        buyer: buyer.publicKey,
+        orderAccount: orderAccount,
+        serviceAccount: serviceAccount,
+      }).signers([buyer]).rpc();
+      expect.fail("buyer refund of a disputed order should be rejected");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("OrderDisputed");
+    }
+
+    // Neither can the escrow be released without the arbiter
+    try {
+      await program.methods.confirmDelivery().accountsPartial({
+        buyer: buyer.publicKey,
+        vendor: otherAsker.publicKey,
+        configAccount: configAccount,
+        orderAccount: orderAccount,
+        serviceAccount: serviceAccount,
+        receiptAccount: PublicKey.findProgramAddressSync(
+          [Buffer.from("receipt"), nftMint.publicKey.toBuffer(), buyer.publicKey.toBuffer()],
+          program.programId
+        )[0],
+      }).remainingAccounts([
+        { pubkey: vendor.publicKey, isWritable: true, isSigner: false },
+        { pubkey: teammate.publicKey, isWritable: true, isSigner: false },
+      ]).signers([buyer]).rpc();
+      expect.fail("confirming a disputed order should be rejected");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("OrderDisputed");
+    }
+
+    const vendorBalanceBefore = await connection.getBalance(otherAsker.publicKey);
+    const treasuryBalanceBefore = await connection.getBalance(treasuryAccount);
+
+    // Split the escrow evenly between the buyer and the vendor
+    await program.methods.resolveDispute(5000).accountsPartial({
+      arbiter: arbiter.publicKey,
+      configAccount: configAccount,
+      buyer: buyer.publicKey,
+      vendor: otherAsker.publicKey,
+      orderAccount: orderAccount,
+      serviceAccount: serviceAccount,
+    }).remainingAccounts([
+      { pubkey: vendor.publicKey, isWritable: true, isSigner: false },
+      { pubkey: teammate.publicKey, isWritable: true, isSigner: false },
+    ]).signers([arbiter]).rpc();
+
+    const vendorBalanceAfter = await connection.getBalance(otherAsker.publicKey);
+    const treasuryBalanceAfter = await connection.getBalance(treasuryAccount);
+    const serviceData = await program.account.serviceAccount.fetch(serviceAccount);
+    const configData = await program.account.configAccount.fetch(configAccount);
+    const vendorShare = serviceData.price.toNumber() / 2;
+
+    // The vendor share pays the protocol fee and the resale royalties like any other sale
+    expect(treasuryBalanceAfter - treasuryBalanceBefore).to.equal(Math.floor(vendorShare * configData.protocolFeeBasisPoints / 10000));
+    expect(vendorBalanceAfter - vendorBalanceBefore).to.be.lessThan(vendorShare - (treasuryBalanceAfter - treasuryBalanceBefore));
+    expect(serviceData.openOrders).to.equal(0);
+    expect(serviceData.currentVendor.toString()).to.equal(otherAsker.publicKey.toString());
+    expect(await program.account.orderAccount.fetchNullable(orderAccount)).to.equal(null);
+  })
+
+  it("Cancel expired ask", async () => {
+    const cranker = await createUserWithLamports(connection, 1);
+    const expiringAsker = await createUserWithLamports(connection, 10);
+    const expiringAskAccount = PublicKey.findProgramAddressSync(
+      [Buffer.from("ask"), nftMint.publicKey.toBuffer(), expiringAsker.publicKey.toBuffer()],
+      program.programId
+    )[0];
+
+    const now = (await connection.getBlockTime(await connection.getSlot())) ?? Math.floor(Date.now() / 1000);
+
+    try {
+      await program.methods.setServiceExpiry(new anchor.BN(now - 60)).accountsPartial({
+        vendor: otherAsker.publicKey,
+        serviceAccount: serviceAccount,
+      }).signers([otherAsker]).rpc();
+      expect.fail("expiry in the past should be rejected");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("InvalidExpiry");
+    }
+
+    await program.methods.askService(askPrice, new anchor.BN(now + 2)).accountsPartial({
+      asker: expiringAsker.publicKey,
+      configAccount: configAccount,
+      nftMint: nftMint.publicKey,
+      serviceAccount: serviceAccount,
+      askAccount: expiringAskAccount,
+    }).signers([expiringAsker]).rpc();
+
+    const cancelAccounts = {
+      cranker: cranker.publicKey,
+      asker: expiringAsker.publicKey,
+      askAccount: expiringAskAccount,
+      serviceAccount: serviceAccount,
+    };
+
+    try {
+      await program.methods.cancelExpiredAsk().accountsPartial(cancelAccounts).signers([cranker]).rpc();
+      expect.fail("live ask should not be cancellable by a cranker");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("AskNotExpired");
+    }
+
+    await new Promise((resolve) => setTimeout(resolve, 4000));
+
+    const crankerBalanceBefore = await connection.getBalance(cranker.publicKey);
+    await program.methods.cancelExpiredAsk().accountsPartial(cancelAccounts).signers([cranker]).rpc();
+    const crankerBalanceAfter = await connection.getBalance(cranker.publicKey);
+
+    // The reward covers more than the transaction fee
+    expect(crankerBalanceAfter).to.be.greaterThan(crankerBalanceBefore);
+    expect(await program.account.askAccount.fetchNullable(expiringAskAccount)).to.equal(null);
+  })
+
+  it("Close service", async () => {
+    const closingVendor = await createUserWithLamports(connection, 10);
+    const closingAsker = await createUserWithLamports(connection, 10);
+    const closingMint = Keypair.generate();
+    const closingServiceAccount = PublicKey.findProgramAddressSync(
+      [Buffer.from("service"), closingMint.publicKey.toBuffer()],
+      program.programId
+    )[0];
+    const closingAskAccount = PublicKey.findProgramAddressSync(
+      [Buffer.from("ask"), closingMint.publicKey.toBuffer(), closingAsker.publicKey.toBuffer()],
+      program.programId
+    )[0];
+    const closingConfigTokenAccount = getAssociatedTokenAddressSync(closingMint.publicKey, configAccount, true, TOKEN_2022_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID);
+
+    await program.methods.createService(false, [], price, null, [], { name: "Closing service", symbol: "CLS", uri: null }).accountsPartial({
+      vendor: closingVendor.publicKey,
+      configAccount: configAccount,
+      nftMint: closingMint.publicKey,
+      serviceAccount: closingServiceAccount,
+      configTokenAccount: closingConfigTokenAccount,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    }).signers([closingVendor, closingMint]).rpc();
+
+    await program.methods.askService(askPrice, null).accountsPartial({
+      asker: closingAsker.publicKey,
+      configAccount: configAccount,
+      nftMint: closingMint.publicKey,
+      serviceAccount: closingServiceAccount,
+      askAccount: closingAskAccount,
+    }).signers([closingAsker]).rpc();
+
+    const closeAccounts = {
+      authority: closingVendor.publicKey,
+      configAccount: configAccount,
+      serviceAccount: closingServiceAccount,
+      nftMint: closingMint.publicKey,
+      configTokenAccount: closingConfigTokenAccount,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    };
+
+    // The open ask has to be refunded along with the close
+    try {
+      await program.methods.closeService().accountsPartial(closeAccounts).signers([closingVendor]).rpc();
+      expect.fail("closing with an open ask should be rejected");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("ServiceHasOpenAsks");
+    }
+
+    const askerBalanceBefore = await connection.getBalance(closingAsker.publicKey);
+
+    await program.methods.closeService().accountsPartial(closeAccounts).remainingAccounts([
+      { pubkey: closingAskAccount, isWritable: true, isSigner: false },
+      { pubkey: closingAsker.publicKey, isWritable: true, isSigner: false },
+    ]).signers([closingVendor]).rpc();
+
+    const askerBalanceAfter = await connection.getBalance(closingAsker.publicKey);
+    expect(askerBalanceAfter - askerBalanceBefore).to.be.greaterThan(askPrice.toNumber());
+    expect(await program.account.askAccount.fetchNullable(closingAskAccount)).to.equal(null);
+    expect(await program.account.serviceAccount.fetchNullable(closingServiceAccount)).to.equal(null);
+    expect(await connection.getAccountInfo(closingConfigTokenAccount)).to.equal(null);
+    expect(await connection.getAccountInfo(closingMint.publicKey)).to.equal(null);
+  })
+
+  it("Dutch auction pricing", async () => {
+    const now = (await connection.getBlockTime(await connection.getSlot())) ?? Math.floor(Date.now() / 1000);
+    const dutchAuction = {
+      startPrice: new anchor.BN(1 * LAMPORTS_PER_SOL),
+      floorPrice: new anchor.BN(0.5 * LAMPORTS_PER_SOL),
+      startTime: new anchor.BN(now - 600),
+      endTime: new anchor.BN(now + 600),
+      stepDuration: new anchor.BN(0),
+    };
+
+    try {
+      await program.methods.setDutchAuction({ ...dutchAuction, floorPrice: new anchor.BN(2 * LAMPORTS_PER_SOL) }).accountsPartial({
+        vendor: otherAsker.publicKey,
+        serviceAccount: serviceAccount,
+      }).signers([otherAsker]).rpc();
+      expect.fail("floor above the start price should be rejected");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("InvalidDutchAuction");
+    }
+
+    await program.methods.setDutchAuction(dutchAuction).accountsPartial({
+      vendor: otherAsker.publicKey,
+      serviceAccount: serviceAccount,
+    }).signers([otherAsker]).rpc();
+
+    // Escrowed orders pay the listed price, so they wait for the Dutch auction to end
+    try {
+      await program.methods.purchaseOrder(dutchAuction.startPrice).accountsPartial({
+        buyer: buyer.publicKey,
+        configAccount: configAccount,
+        serviceAccount: serviceAccount,
+        orderAccount: PublicKey.findProgramAddressSync(
+          [Buffer.from("order"), nftMint.publicKey.toBuffer(), buyer.publicKey.toBuffer()],
+          program.programId
+        )[0],
+      }).signers([buyer]).rpc();
+      expect.fail("escrowed order during a Dutch auction should be rejected");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("ServiceInDutchAuction");
+    }
+
+    // Halfway through the window the price is around 0.75 SOL, above what the buyer accepts
+    try {
+      await program.methods.buyService(dutchAuction.floorPrice).accountsPartial({
+        buyer: buyer.publicKey,
+        configAccount: configAccount,
+        serviceAccount: serviceAccount,
+        currentVendor: otherAsker.publicKey,
+        originalVendor: vendor.publicKey,
+      }).signers([buyer]).rpc();
+      expect.fail("price above the buyer's maximum should be rejected");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("PriceAboveMaximum");
+    }
+
+    await program.methods.setDutchAuction(null).accountsPartial({
+      vendor: otherAsker.publicKey,
+      serviceAccount: serviceAccount,
+    }).signers([otherAsker]).rpc();
+
+    const serviceData = await program.account.serviceAccount.fetch(serviceAccount);
+    expect(serviceData.dutchAuction).to.equal(null);
+  })
+
+  it("English auction", async () => {
+    const auctionAccount = PublicKey.findProgramAddressSync(
+      [Buffer.from("auction"), nftMint.publicKey.toBuffer()],
+      program.programId
+    )[0];
+    const reservePrice = new anchor.BN(0.2 * LAMPORTS_PER_SOL);
+    const minBidIncrement = new anchor.BN(0.05 * LAMPORTS_PER_SOL);
+
+    const startAccounts = {
+      seller: otherAsker.publicKey,
+      configAccount: configAccount,
+      serviceAccount: serviceAccount,
+      auctionAccount: auctionAccount,
+    };
+
+    // An auction without bids leaves the service with the seller
+    await program.methods.startAuction(reservePrice, minBidIncrement, new anchor.BN(2)).accountsPartial(startAccounts).signers([otherAsker]).rpc();
+
+    try {
+      await program.methods.buyService(price).accountsPartial({
+        buyer: buyer.publicKey,
+        configAccount: configAccount,
+        serviceAccount: serviceAccount,
+        currentVendor: otherAsker.publicKey,
+        originalVendor: vendor.publicKey,
+      }).signers([buyer]).rpc();
+      expect.fail("buying a service under auction should be rejected");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("ServiceInAuction");
+    }
+
+    await new Promise((resolve) => setTimeout(resolve, 3000));
+
+    await program.methods.settleAuction().accountsPartial({
+      settler: buyer.publicKey,
+      seller: otherAsker.publicKey,
+      configAccount: configAccount,
+      serviceAccount: serviceAccount,
+      auctionAccount: auctionAccount,
+    }).signers([buyer]).rpc();
+
+    let serviceData = await program.account.serviceAccount.fetch(serviceAccount);
+    expect(serviceData.isInAuction).to.equal(false);
+    expect(serviceData.currentVendor.toString()).to.equal(otherAsker.publicKey.toString());
+
+    await program.methods.startAuction(reservePrice, minBidIncrement, new anchor.BN(3600)).accountsPartial(startAccounts).signers([otherAsker]).rpc();
+
+    try {
+      await program.methods.placeBid(reservePrice.subn(1)).accountsPartial({
+        bidder: asker.publicKey,
+        configAccount: configAccount,
+        serviceAccount: serviceAccount,
+        auctionAccount: auctionAccount,
+      }).signers([asker]).rpc();
+      expect.fail("bid below the reserve should be rejected");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("BidTooLow");
+    }
+
+    await program.methods.placeBid(reservePrice).accountsPartial({
+      bidder: asker.publicKey,
+      configAccount: configAccount,
+      serviceAccount: serviceAccount,
+      auctionAccount: auctionAccount,
+    }).signers([asker]).rpc();
+
+    // Outbidding refunds the previous bidder
+    const askerBalanceBefore = await connection.getBalance(asker.publicKey);
+    await program.methods.placeBid(reservePrice.add(minBidIncrement)).accountsPartial({
+      bidder: buyer.publicKey,
+      configAccount: configAccount,
+      serviceAccount: serviceAccount,
+      auctionAccount: auctionAccount,
+      previousBidder: asker.publicKey,
+    }).signers([buyer]).rpc();
+    const askerBalanceAfter = await connection.getBalance(asker.publicKey);
+    expect(askerBalanceAfter - askerBalanceBefore).to.equal(reservePrice.toNumber());
+
+    const auctionData = await program.account.auctionAccount.fetch(auctionAccount);
+    expect(auctionData.highestBidder.toString()).to.equal(buyer.publicKey.toString());
+    expect(auctionData.highestBid.toString()).to.equal(reservePrice.add(minBidIncrement).toString());
+
+    try {
+      await program.methods.cancelAuction().accountsPartial({
+        seller: otherAsker.publicKey,
+        serviceAccount: serviceAccount,
+        auctionAccount: auctionAccount,
+      }).signers([otherAsker]).rpc();
+      expect.fail("auction with bids should not be cancellable");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("AuctionHasBids");
+    }
+  })
+
+  it("Multi-unit service", async () => {
+    const unitVendor = await createUserWithLamports(connection, 10);
+    const unitBuyers = [await createUserWithLamports(connection, 10), await createUserWithLamports(connection, 10)];
+    const unitNftMint = Keypair.generate();
+    const unitServiceAccount = PublicKey.findProgramAddressSync(
+      [Buffer.from("service"), unitNftMint.publicKey.toBuffer()],
+      program.programId
+    )[0];
+    const unitMint = PublicKey.findProgramAddressSync(
+      [Buffer.from("unit_mint"), unitNftMint.publicKey.toBuffer()],
+      program.programId
+    )[0];
+
+    await program.methods.createService(false, [], price, null, [], { name: "Multi-unit service", symbol: "UNIT", uri: null }).accountsPartial({
+      vendor: unitVendor.publicKey,
+      configAccount: configAccount,
+      nftMint: unitNftMint.publicKey,
+      serviceAccount: unitServiceAccount,
+      configTokenAccount: getAssociatedTokenAddressSync(unitNftMint.publicKey, configAccount, true, TOKEN_2022_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID),
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    }).signers([unitVendor, unitNftMint]).rpc();
+
+    await program.methods.enableInventory(1).accountsPartial({
+      vendor: unitVendor.publicKey,
+      configAccount: configAccount,
+      serviceAccount: unitServiceAccount,
+      unitMint: unitMint,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    }).signers([unitVendor]).rpc();
+
+    const buyUnit = (unitBuyer: Signer) => program.methods.buyService(price).accountsPartial({
+      buyer: unitBuyer.publicKey,
+      configAccount: configAccount,
+      serviceAccount: unitServiceAccount,
+      currentVendor: unitVendor.publicKey,
+      originalVendor: unitVendor.publicKey,
+      unitMint: unitMint,
+      buyerUnitTokenAccount: getAssociatedTokenAddressSync(unitMint, unitBuyer.publicKey, false, TOKEN_2022_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID),
+      unitTokenProgram: TOKEN_2022_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+    }).signers([unitBuyer]).rpc();
+
+    try {
+      await buyUnit(unitVendor);
+      expect.fail("vendor buying their own service should be rejected");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("CannotBuyOwnService");
+    }
+
+    // Units are not auctioned, the whole listing would go to the winner
+    try {
+      await program.methods.startAuction(price, price, new anchor.BN(3600)).accountsPartial({
+        seller: unitVendor.publicKey,
+        configAccount: configAccount,
+        serviceAccount: unitServiceAccount,
+        auctionAccount: PublicKey.findProgramAddressSync(
+          [Buffer.from("auction"), unitNftMint.publicKey.toBuffer()],
+          program.programId
+        )[0],
+      }).signers([unitVendor]).rpc();
+      expect.fail("auctioning a multi-unit service should be rejected");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("InventoryAlreadyEnabled");
+    }
+
+    // Neither can the listing be sold through an ask
+    try {
+      await program.methods.askService(price, null).accountsPartial({
+        asker: unitBuyers[0].publicKey,
+        configAccount: configAccount,
+        askAccount: PublicKey.findProgramAddressSync(
+          [Buffer.from("ask"), unitNftMint.publicKey.toBuffer(), unitBuyers[0].publicKey.toBuffer()],
+          program.programId
+        )[0],
+        nftMint: unitNftMint.publicKey,
+        serviceAccount: unitServiceAccount,
+      }).signers([unitBuyers[0]]).rpc();
+      expect.fail("asking for a multi-unit service should be rejected");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("InventoryAlreadyEnabled");
+    }
+
+    // Each purchase mints a unit to the buyer, the listing stays with the vendor
+    await buyUnit(unitBuyers[0]);
+
+    try {
+      await buyUnit(unitBuyers[1]);
+      expect.fail("buying a sold out service should be rejected");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("ServiceSoldOut");
+    }
+
+    await program.methods.restockService(2).accountsPartial({
+      vendor: unitVendor.publicKey,
+      serviceAccount: unitServiceAccount,
+    }).signers([unitVendor]).rpc();
+
+    await buyUnit(unitBuyers[1]);
+
+    const serviceData = await program.account.serviceAccount.fetch(unitServiceAccount);
+    expect(serviceData.currentVendor.toString()).to.equal(unitVendor.publicKey.toString());
+    expect(serviceData.unitMint.toString()).to.equal(unitMint.toString());
+    expect(serviceData.capacity).to.equal(3);
+    expect(serviceData.remainingUnits).to.equal(1);
+
+    for (const unitBuyer of unitBuyers) {
+      const unitTokenAccount = await getAccount(
+        connection,
+        getAssociatedTokenAddressSync(unitMint, unitBuyer.publicKey, false, TOKEN_2022_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID),
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      );
+      expect(unitTokenAccount.amount.toString()).to.equal("1");
+    }
+
+    // Buying again does not reopen a reviewed receipt
+    const reviewAccounts = {
+      buyer: unitBuyers[0].publicKey,
+      receiptAccount: PublicKey.findProgramAddressSync(
+        [Buffer.from("receipt"), unitNftMint.publicKey.toBuffer(), unitBuyers[0].publicKey.toBuffer()],
+        program.programId
+      )[0],
+      serviceAccount: unitServiceAccount,
+      vendorProfile: PublicKey.findProgramAddressSync(
+        [Buffer.from("vendor_profile"), unitVendor.publicKey.toBuffer()],
+        program.programId
+      )[0],
+    };
+    const commentHash = Array.from(Buffer.alloc(32, 1));
+    await program.methods.submitReview(5, commentHash).accountsPartial(reviewAccounts).signers([unitBuyers[0]]).rpc();
+    await buyUnit(unitBuyers[0]);
+
+    try {
+      await program.methods.submitReview(5, commentHash).accountsPartial(reviewAccounts).signers([unitBuyers[0]]).rpc();
+      expect.fail("review after a repeat purchase should be rejected");
+    } catch (err) {
+      expect(err.error.errorCode.code).to.equal("PurchaseAlreadyReviewed");
+    }
+
+    const reviewedServiceData = await program.account.serviceAccount.fetch(unitServiceAccount);
+    expect(reviewedServiceData.rating.count).to.equal(1);
+    expect(reviewedServiceData.remainingUnits).to.equal(0);
   })
 });
 